@@ -0,0 +1,175 @@
+use std::cmp::Ordering;
+
+use pyo3::prelude::*;
+
+#[pyfunction]
+#[pyo3(signature = (data, descending=false))]
+pub fn argsort(data: Vec<f64>, descending: bool) -> PyResult<Vec<usize>> {
+    let mut order: Vec<usize> = (0..data.len()).collect();
+    // Stable sort keeps equal values in input order; NaN sorts last either way, whatever its sign
+    // bit, which total_cmp alone would send to the front
+    order.sort_by(|&a, &b| match (data[a].is_nan(), data[b].is_nan()) {
+        (false, false) if descending => data[b].total_cmp(&data[a]),
+        (false, false) => data[a].total_cmp(&data[b]),
+        (x, y) => x.cmp(&y),
+    });
+    Ok(order)
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, k, largest=true))]
+pub fn top_k(data: Vec<f64>, k: usize, largest: bool) -> PyResult<(Vec<f64>, Vec<usize>)> {
+    if k > data.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("k cannot exceed the data length"));
+    }
+    if data.iter().any(|x| x.is_nan()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot contain NaN"));
+    }
+    if k == 0 {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let compare = |a: &usize, b: &usize| -> Ordering {
+        let by_value = if largest { data[*b].total_cmp(&data[*a]) } else { data[*a].total_cmp(&data[*b]) };
+        by_value.then(a.cmp(b))
+    };
+    // Partial selection puts the k best in front, only those get sorted
+    let mut order: Vec<usize> = (0..data.len()).collect();
+    if k < order.len() {
+        order.select_nth_unstable_by(k - 1, compare);
+        order.truncate(k);
+    }
+    order.sort_unstable_by(compare);
+    Ok((order.iter().map(|&i| data[i]).collect(), order))
+}
+
+#[pyfunction]
+pub fn argmax(data: Vec<f64>) -> PyResult<usize> {
+    data.iter()
+        .enumerate()
+        .filter(|(_, x)| !x.is_nan())
+        .fold(None, |best: Option<(usize, f64)>, (i, &x)| match best {
+            Some((_, b)) if b >= x => best,
+            _ => Some((i, x)),
+        })
+        .map(|(i, _)| i)
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Data must contain at least one non-NaN value"))
+}
+
+#[pyfunction]
+pub fn argmin(data: Vec<f64>) -> PyResult<usize> {
+    data.iter()
+        .enumerate()
+        .filter(|(_, x)| !x.is_nan())
+        .fold(None, |best: Option<(usize, f64)>, (i, &x)| match best {
+            Some((_, b)) if b <= x => best,
+            _ => Some((i, x)),
+        })
+        .map(|(i, _)| i)
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Data must contain at least one non-NaN value"))
+}
+
+#[pyfunction]
+#[pyo3(signature = (sorted_array, values, side="left"))]
+pub fn searchsorted(sorted_array: Vec<f64>, values: Vec<f64>, side: &str) -> PyResult<Vec<usize>> {
+    if sorted_array.windows(2).any(|w| w[0] > w[1]) {
+        return Err(pyo3::exceptions::PyValueError::new_err("sorted_array must be sorted in ascending order"));
+    }
+    let right = match side {
+        "left" => false,
+        "right" => true,
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("side must be 'left' or 'right'")),
+    };
+    Ok(values
+        .iter()
+        .map(|&v| if right { sorted_array.partition_point(|&x| x <= v) } else { sorted_array.partition_point(|&x| x < v) })
+        .collect())
+}
+
+#[pyfunction]
+pub fn value_counts(codes: Vec<usize>, n_bins: usize) -> PyResult<Vec<usize>> {
+    let mut counts = vec![0usize; n_bins];
+    for &c in &codes {
+        match counts.get_mut(c) {
+            Some(slot) => *slot += 1,
+            None => return Err(pyo3::exceptions::PyValueError::new_err(format!("Code {} is out of range for {} bins", c, n_bins))),
+        }
+    }
+    Ok(counts)
+}
+
+fn sorted_unique_counts(data: &[f64]) -> (Vec<f64>, Vec<usize>) {
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mut values: Vec<f64> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    for x in sorted {
+        // NaNs collapse into a single trailing entry
+        match values.last() {
+            Some(&last) if last == x || (last.is_nan() && x.is_nan()) => *counts.last_mut().unwrap() += 1,
+            _ => {
+                values.push(x);
+                counts.push(1);
+            }
+        }
+    }
+    (values, counts)
+}
+
+#[pyfunction]
+pub fn unique(data: Vec<f64>) -> PyResult<Vec<f64>> {
+    Ok(sorted_unique_counts(&data).0)
+}
+
+#[pyfunction]
+pub fn unique_counts(data: Vec<f64>) -> PyResult<(Vec<f64>, Vec<usize>)> {
+    Ok(sorted_unique_counts(&data))
+}
+
+fn check_sorted(a: &[i64], b: &[i64]) -> PyResult<()> {
+    if a.windows(2).any(|w| w[0] > w[1]) || b.windows(2).any(|w| w[0] > w[1]) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Inputs must be sorted in ascending order"));
+    }
+    Ok(())
+}
+
+#[pyfunction]
+pub fn intersect_sorted(a: Vec<i64>, b: Vec<i64>) -> PyResult<Vec<i64>> {
+    check_sorted(&a, &b)?;
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(a.len().min(b.len()));
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                if out.last() != Some(&a[i]) {
+                    out.push(a[i]);
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[pyfunction]
+pub fn union_sorted(a: Vec<i64>, b: Vec<i64>) -> PyResult<Vec<i64>> {
+    check_sorted(&a, &b)?;
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    while i < a.len() || j < b.len() {
+        let next = if j >= b.len() || (i < a.len() && a[i] <= b[j]) {
+            i += 1;
+            a[i - 1]
+        } else {
+            j += 1;
+            b[j - 1]
+        };
+        if out.last() != Some(&next) {
+            out.push(next);
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,280 @@
+use pyo3::prelude::*;
+
+use crate::input::{ArrayLike, Element};
+use crate::options::{norm_cdf, norm_ppf};
+use crate::progress::{Progress, Step};
+use crate::rng::Rng;
+use crate::with_slice;
+
+// Politis-Romano stationary bootstrap: blocks start at random points and have geometric lengths with
+// mean avg_block_length, wrapping around the end, so short-range autocorrelation survives resampling.
+// `progress(done, n_paths)` is called every `progress_every` paths
+#[pyfunction]
+#[pyo3(signature = (returns, avg_block_length, n_paths, seed=None, progress=None, progress_every=100))]
+pub fn stationary_bootstrap<'py>(
+    py: Python<'py>,
+    returns: ArrayLike<'py>,
+    avg_block_length: f64,
+    n_paths: usize,
+    seed: Option<u64>,
+    progress: Option<Bound<'py, PyAny>>,
+    progress_every: usize,
+) -> PyResult<Vec<Vec<f64>>> {
+    if avg_block_length < 1.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("avg_block_length must be at least 1"));
+    }
+    let progress = Progress::new(progress, progress_every, n_paths)?;
+    let mut rng = Rng::from_seed(seed);
+    with_slice!(returns, returns => {
+        if returns.is_empty() {
+            return Err(pyo3::exceptions::PyValueError::new_err("Returns cannot be empty"));
+        }
+        py.detach(|| bootstrap_paths(returns, avg_block_length, n_paths, &mut rng, &progress))
+    })
+}
+
+fn bootstrap_paths<T: Element, P: Step>(
+    returns: &[T],
+    avg_block_length: f64,
+    n_paths: usize,
+    rng: &mut Rng,
+    progress: &P,
+) -> Result<Vec<Vec<f64>>, P::Error> {
+    let n = returns.len();
+    let restart = 1.0 / avg_block_length;
+    (0..n_paths)
+        .map(|_| {
+            let mut idx = rng.below(n);
+            let path = (0..n)
+                .map(|step| {
+                    if step > 0 {
+                        idx = if rng.next_f64() < restart { rng.below(n) } else { (idx + 1) % n };
+                    }
+                    returns[idx].to_f64()
+                })
+                .collect();
+            progress.step()?;
+            Ok(path)
+        })
+        .collect()
+}
+
+// Standard error of a per-period Sharpe estimate under non-normal returns (kurtosis is not excess)
+fn sharpe_std(sharpe: f64, skew: f64, kurtosis: f64, n_obs: usize) -> PyResult<f64> {
+    if n_obs < 2 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_obs must be at least 2"));
+    }
+    let var = 1.0 - skew * sharpe + 0.25 * (kurtosis - 1.0) * sharpe * sharpe;
+    if var <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "skew and kurtosis give a non-positive Sharpe variance",
+        ));
+    }
+    Ok((var / (n_obs - 1) as f64).sqrt())
+}
+
+// Probability that the true Sharpe exceeds benchmark_sharpe; both are per-period, not annualized
+#[pyfunction]
+#[pyo3(signature = (sharpe, benchmark_sharpe=0.0, skew=0.0, kurtosis=3.0, n_obs=252))]
+pub fn probabilistic_sharpe(sharpe: f64, benchmark_sharpe: f64, skew: f64, kurtosis: f64, n_obs: usize) -> PyResult<f64> {
+    Ok(norm_cdf((sharpe - benchmark_sharpe) / sharpe_std(sharpe, skew, kurtosis, n_obs)?))
+}
+
+// PSR against the expected maximum Sharpe of n_trials skill-less strategies. trials_sharpe_variance is the
+// variance of Sharpe ratios across the trials; by default the null estimator variance 1 / (n_obs - 1)
+#[pyfunction]
+#[pyo3(signature = (sharpe, n_trials, skew=0.0, kurtosis=3.0, n_obs=252, trials_sharpe_variance=None))]
+pub fn deflated_sharpe(
+    sharpe: f64,
+    n_trials: usize,
+    skew: f64,
+    kurtosis: f64,
+    n_obs: usize,
+    trials_sharpe_variance: Option<f64>,
+) -> PyResult<f64> {
+    if n_trials == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_trials must be positive"));
+    }
+    if n_obs < 2 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_obs must be at least 2"));
+    }
+    let variance = trials_sharpe_variance.unwrap_or(1.0 / (n_obs - 1) as f64);
+    if variance < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("trials_sharpe_variance cannot be negative"));
+    }
+    let benchmark = if n_trials == 1 {
+        0.0
+    } else {
+        const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+        let n = n_trials as f64;
+        variance.sqrt()
+            * ((1.0 - EULER_GAMMA) * norm_ppf(1.0 - 1.0 / n) + EULER_GAMMA * norm_ppf(1.0 - 1.0 / (n * std::f64::consts::E)))
+    };
+    probabilistic_sharpe(sharpe, benchmark, skew, kurtosis, n_obs)
+}
+
+// Training indices left after removing the test blocks, any observation whose label interval overlaps a
+// test block's label span, and an embargo of observations right after each block
+fn purged_train(test: &[bool], starts: &[usize], ends: &[usize], embargo: usize) -> Vec<usize> {
+    let n = test.len();
+    let mut keep: Vec<bool> = test.iter().map(|&t| !t).collect();
+    let mut i = 0;
+    while i < n {
+        if !test[i] {
+            i += 1;
+            continue;
+        }
+        let block_start = i;
+        while i < n && test[i] {
+            i += 1;
+        }
+        let block_end = i - 1;
+        let span_start = starts[block_start..=block_end].iter().copied().min().unwrap();
+        let span_end = ends[block_start..=block_end].iter().copied().max().unwrap();
+        for j in 0..n {
+            if keep[j] && starts[j] <= span_end && ends[j] >= span_start {
+                keep[j] = false;
+            }
+        }
+        for flag in keep.iter_mut().skip(block_end + 1).take(embargo) {
+            *flag = false;
+        }
+    }
+    keep.iter().enumerate().filter(|(_, &k)| k).map(|(j, _)| j).collect()
+}
+
+// Every k-subset of 0..n in lexicographic order
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut result = Vec::new();
+    let mut combo: Vec<usize> = (0..k).collect();
+    loop {
+        result.push(combo.clone());
+        let Some(pos) = (0..k).rev().find(|&p| combo[p] < n - k + p) else {
+            return result;
+        };
+        combo[pos] += 1;
+        for p in pos + 1..k {
+            combo[p] = combo[p - 1] + 1;
+        }
+    }
+}
+
+// Combinatorially purged CV: n_obs split into n_groups contiguous groups, every choice of n_test_groups
+// forms a test set. label_end_times[i] is the index where observation i's label is resolved
+#[pyfunction]
+#[pyo3(signature = (n_obs, n_groups, n_test_groups, embargo_pct=0.0, label_end_times=None))]
+pub fn cpcv_splits(
+    n_obs: usize,
+    n_groups: usize,
+    n_test_groups: usize,
+    embargo_pct: f64,
+    label_end_times: Option<Vec<usize>>,
+) -> PyResult<Vec<(Vec<usize>, Vec<usize>)>> {
+    if n_groups < 2 || n_groups > n_obs {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_groups must be between 2 and n_obs"));
+    }
+    if n_test_groups == 0 || n_test_groups >= n_groups {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_test_groups must be between 1 and n_groups - 1"));
+    }
+    if !(0.0..1.0).contains(&embargo_pct) {
+        return Err(pyo3::exceptions::PyValueError::new_err("embargo_pct must be in [0, 1)"));
+    }
+    let ends = label_end_times.unwrap_or_else(|| (0..n_obs).collect());
+    if ends.len() != n_obs || ends.iter().enumerate().any(|(i, &e)| e < i) {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "label_end_times must have n_obs entries, none before its own index",
+        ));
+    }
+    let embargo = (embargo_pct * n_obs as f64).ceil() as usize;
+    Ok(cpcv(n_groups, n_test_groups, embargo, &ends))
+}
+
+fn cpcv(n_groups: usize, n_test_groups: usize, embargo: usize, ends: &[usize]) -> Vec<(Vec<usize>, Vec<usize>)> {
+    let n_obs = ends.len();
+    let starts: Vec<usize> = (0..n_obs).collect();
+    // Groups differ in size by at most one observation
+    let bounds: Vec<usize> = (0..=n_groups).map(|g| g * n_obs / n_groups).collect();
+    combinations(n_groups, n_test_groups)
+        .into_iter()
+        .map(|groups| {
+            let mut test = vec![false; n_obs];
+            for &g in &groups {
+                test[bounds[g]..bounds[g + 1]].iter_mut().for_each(|t| *t = true);
+            }
+            let test_idx = (0..n_obs).filter(|&i| test[i]).collect();
+            (purged_train(&test, &starts, ends, embargo), test_idx)
+        })
+        .collect()
+}
+
+// K contiguous test folds; label_intervals[i] = (start, end) gives the observation indices spanned by
+// label i, and embargo is the number of observations dropped after each test fold
+#[pyfunction]
+#[pyo3(signature = (n, k, label_intervals=None, embargo=0))]
+pub fn purged_kfold_indices(
+    n: usize,
+    k: usize,
+    label_intervals: Option<Vec<(usize, usize)>>,
+    embargo: usize,
+) -> PyResult<Vec<(Vec<usize>, Vec<usize>)>> {
+    if k < 2 || k > n {
+        return Err(pyo3::exceptions::PyValueError::new_err("k must be between 2 and n"));
+    }
+    let intervals = label_intervals.unwrap_or_else(|| (0..n).map(|i| (i, i)).collect());
+    if intervals.len() != n || intervals.iter().any(|&(s, e)| e < s) {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "label_intervals must have n entries with start <= end",
+        ));
+    }
+    let (starts, ends): (Vec<usize>, Vec<usize>) = intervals.into_iter().unzip();
+    Ok((0..k)
+        .map(|fold| {
+            let (lo, hi) = (fold * n / k, (fold + 1) * n / k);
+            let test: Vec<bool> = (0..n).map(|i| (lo..hi).contains(&i)).collect();
+            (purged_train(&test, &starts, &ends, embargo), (lo..hi).collect())
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::Silent;
+
+    #[test]
+    fn bootstrap_paths_are_pinned_for_a_seed() {
+        let returns: Vec<f64> = (0..8).map(|i| i as f64).collect();
+        let Ok(paths) = bootstrap_paths(&returns, 3.0, 2, &mut Rng::new(5), &Silent);
+        assert_eq!(
+            paths,
+            [[2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 0.0, 1.0], [2.0, 3.0, 4.0, 2.0, 3.0, 4.0, 5.0, 6.0]]
+        );
+    }
+
+    #[test]
+    fn cpcv_purges_and_embargoes_around_each_test_block() {
+        // 12 observations in 4 groups of 3, 2 test groups; each label resolves one bar later
+        let ends: Vec<usize> = (0..12).map(|i| (i + 1).min(11)).collect();
+        let splits = cpcv(4, 2, 0, &ends);
+        assert_eq!(splits.len(), 6);
+        // Every group is tested C(3, 1) = 3 times, so the splits recombine into 3 backtest paths
+        for group in [0, 3, 6, 9] {
+            assert_eq!(splits.iter().filter(|(_, test)| test.contains(&group)).count(), 3);
+        }
+
+        // Groups 0 and 1 tested: observation 6 is purged since its label starts where the block's ends
+        assert_eq!(splits[0].1, (0..6).collect::<Vec<_>>());
+        assert_eq!(splits[0].0, [7, 8, 9, 10, 11]);
+        // Groups 0 and 2: 3 and 9 start inside a block's label span, 5 ends inside one
+        assert_eq!(splits[1].1, [0, 1, 2, 6, 7, 8]);
+        assert_eq!(splits[1].0, [4, 10, 11]);
+
+        // embargo_pct = 0.15 of 12 observations rounds up to a 2-bar embargo after each block
+        let embargoed = cpcv(4, 2, 2, &ends);
+        assert_eq!(embargoed[0].0, [8, 9, 10, 11]);
+        assert_eq!(embargoed[1].0, [11]);
+        // The last group has nothing after it to embargo
+        assert_eq!(embargoed[5].1, [6, 7, 8, 9, 10, 11]);
+        assert_eq!(embargoed[5].0, [0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,228 @@
+use pyo3::prelude::*;
+
+use crate::progress::{Progress, Step};
+use crate::state::{persist_methods, persist_struct, Persist, Reader, Writer};
+
+#[derive(Clone)]
+enum Node {
+    Leaf(f64),
+    // Rows with x[feature] <= threshold go left
+    Split { feature: usize, threshold: f64, left: usize, right: usize },
+}
+
+#[derive(Clone)]
+struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn predict(&self, row: &[f64]) -> f64 {
+        let mut i = 0;
+        loop {
+            match self.nodes[i] {
+                Node::Leaf(value) => return value,
+                Node::Split { feature, threshold, left, right } => {
+                    i = if row[feature] <= threshold { left } else { right };
+                }
+            }
+        }
+    }
+}
+
+// Lowest-SSE split over every feature and midpoint; None when no split improves on a leaf.
+// Features are passed column-major
+fn best_split(columns: &[Vec<f64>], target: &[f64], rows: &[usize], min_leaf: usize) -> Option<(usize, f64)> {
+    let n = rows.len() as f64;
+    let total: f64 = rows.iter().map(|&r| target[r]).sum();
+    let mut best_gain = 1e-12;
+    let mut best = None;
+    let mut order = rows.to_vec();
+    for (feature, column) in columns.iter().enumerate() {
+        order.sort_by(|&a, &b| column[a].total_cmp(&column[b]));
+        let mut left_sum = 0.0;
+        for (k, pair) in order.windows(2).enumerate() {
+            left_sum += target[pair[0]];
+            let n_left = k + 1;
+            let (lo, hi) = (column[pair[0]], column[pair[1]]);
+            if lo == hi || n_left < min_leaf || rows.len() - n_left < min_leaf {
+                continue;
+            }
+            // SSE reduction equals the gain in sum^2 / count over the parent
+            let right_sum = total - left_sum;
+            let gain = left_sum * left_sum / n_left as f64 + right_sum * right_sum / (n - n_left as f64)
+                - total * total / n;
+            if gain > best_gain {
+                best_gain = gain;
+                best = Some((feature, 0.5 * (lo + hi)));
+            }
+        }
+    }
+    best
+}
+
+fn grow(columns: &[Vec<f64>], target: &[f64], rows: Vec<usize>, depth: usize, min_leaf: usize, nodes: &mut Vec<Node>) -> usize {
+    let id = nodes.len();
+    let mean = rows.iter().map(|&r| target[r]).sum::<f64>() / rows.len() as f64;
+    nodes.push(Node::Leaf(mean));
+    if depth == 0 {
+        return id;
+    }
+    if let Some((feature, threshold)) = best_split(columns, target, &rows, min_leaf) {
+        let (l, r): (Vec<usize>, Vec<usize>) = rows.iter().partition(|&&i| columns[feature][i] <= threshold);
+        let left = grow(columns, target, l, depth - 1, min_leaf, nodes);
+        let right = grow(columns, target, r, depth - 1, min_leaf, nodes);
+        nodes[id] = Node::Split { feature, threshold, left, right };
+    }
+    id
+}
+
+#[pyclass(module = "fina")]
+#[derive(Clone)]
+pub struct GbmModel {
+    #[pyo3(get)]
+    base: f64,
+    #[pyo3(get)]
+    learning_rate: f64,
+    #[pyo3(get)]
+    n_features: usize,
+    trees: Vec<Tree>,
+}
+
+impl GbmModel {
+    fn predict_row(&self, row: &[f64]) -> f64 {
+        self.base + self.learning_rate * self.trees.iter().map(|t| t.predict(row)).sum::<f64>()
+    }
+}
+
+persist_methods!(impl GbmModel {
+    #[getter]
+    fn n_rounds(&self) -> usize {
+        self.trees.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("GbmModel(n_rounds={}, learning_rate={}, n_features={})", self.trees.len(), self.learning_rate, self.n_features)
+    }
+});
+
+// Least-squares gradient boosting: each round fits a shallow regression tree to the residuals.
+// `progress(done, n_rounds)` is called every `progress_every` rounds
+#[pyfunction]
+#[pyo3(signature = (x_2d, y, n_rounds=100, learning_rate=0.1, max_depth=1, min_samples_leaf=1, progress=None, progress_every=10))]
+#[allow(clippy::too_many_arguments)]
+pub fn gbm_fit<'py>(
+    py: Python<'py>,
+    x_2d: Vec<Vec<f64>>,
+    y: Vec<f64>,
+    n_rounds: usize,
+    learning_rate: f64,
+    max_depth: usize,
+    min_samples_leaf: usize,
+    progress: Option<Bound<'py, PyAny>>,
+    progress_every: usize,
+) -> PyResult<GbmModel> {
+    if x_2d.is_empty() || x_2d.len() != y.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("X and y must be non-empty with the same number of rows"));
+    }
+    let p = x_2d[0].len();
+    if p == 0 || x_2d.iter().any(|row| row.len() != p) {
+        return Err(pyo3::exceptions::PyValueError::new_err("X rows must be non-empty and equal length"));
+    }
+    if x_2d.iter().flatten().chain(&y).any(|v| !v.is_finite()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Inputs must be finite"));
+    }
+    if !(1..=3).contains(&max_depth) {
+        return Err(pyo3::exceptions::PyValueError::new_err("max_depth must be 1, 2 or 3"));
+    }
+    if !learning_rate.is_finite() || learning_rate <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("learning_rate must be positive"));
+    }
+    let progress = Progress::new(progress, progress_every, n_rounds)?;
+    py.detach(|| fit_gbm(&x_2d, &y, n_rounds, learning_rate, max_depth, min_samples_leaf.max(1), &progress))
+}
+
+fn fit_gbm<P: Step>(
+    x_2d: &[Vec<f64>],
+    y: &[f64],
+    n_rounds: usize,
+    learning_rate: f64,
+    max_depth: usize,
+    min_leaf: usize,
+    progress: &P,
+) -> Result<GbmModel, P::Error> {
+    let p = x_2d[0].len();
+    let columns: Vec<Vec<f64>> = (0..p).map(|j| x_2d.iter().map(|row| row[j]).collect()).collect();
+    let base = y.iter().sum::<f64>() / y.len() as f64;
+    let mut prediction = vec![base; y.len()];
+    let mut trees = Vec::with_capacity(n_rounds);
+    for _ in 0..n_rounds {
+        let residual: Vec<f64> = y.iter().zip(&prediction).map(|(a, b)| a - b).collect();
+        let mut nodes = Vec::new();
+        grow(&columns, &residual, (0..y.len()).collect(), max_depth, min_leaf, &mut nodes);
+        let tree = Tree { nodes };
+        for (pred, row) in prediction.iter_mut().zip(x_2d) {
+            *pred += learning_rate * tree.predict(row);
+        }
+        trees.push(tree);
+        progress.step()?;
+    }
+    Ok(GbmModel { base, learning_rate, n_features: p, trees })
+}
+
+#[pyfunction]
+pub fn gbm_predict(model: PyRef<GbmModel>, x: Vec<Vec<f64>>) -> PyResult<Vec<f64>> {
+    if x.iter().any(|row| row.len() != model.n_features) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Rows must have n_features columns"));
+    }
+    Ok(x.iter().map(|row| model.predict_row(row)).collect())
+}
+
+impl Persist for Node {
+    fn save(&self, w: &mut Writer) {
+        match *self {
+            Node::Leaf(value) => {
+                0u8.save(w);
+                value.save(w);
+            }
+            Node::Split { feature, threshold, left, right } => {
+                1u8.save(w);
+                feature.save(w);
+                threshold.save(w);
+                left.save(w);
+                right.save(w);
+            }
+        }
+    }
+
+    fn load(r: &mut Reader) -> PyResult<Self> {
+        match u8::load(r)? {
+            0 => Ok(Node::Leaf(f64::load(r)?)),
+            1 => Ok(Node::Split {
+                feature: Persist::load(r)?,
+                threshold: Persist::load(r)?,
+                left: Persist::load(r)?,
+                right: Persist::load(r)?,
+            }),
+            _ => Err(crate::state::corrupt()),
+        }
+    }
+}
+
+// Children always follow their parent, which rules out cycles in a loaded tree
+persist_struct!(
+    Tree { nodes },
+    check = |t| {
+        !t.nodes.is_empty()
+            && t.nodes.iter().enumerate().all(|(i, node)| match *node {
+                Node::Leaf(_) => true,
+                Node::Split { left, right, .. } => left > i && right > i && left < t.nodes.len() && right < t.nodes.len(),
+            })
+    }
+);
+persist_struct!(
+    GbmModel { base, learning_rate, n_features, trees },
+    check = |m| m.trees.iter().flat_map(|t| &t.nodes).all(|node| match *node {
+        Node::Leaf(_) => true,
+        Node::Split { feature, .. } => feature < m.n_features,
+    })
+);
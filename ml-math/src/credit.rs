@@ -0,0 +1,156 @@
+use pyo3::prelude::*;
+
+use crate::errors::Invalid;
+
+#[pyfunction]
+pub fn ks_statistic(scores: Vec<f64>, labels: Vec<u8>) -> PyResult<f64> {
+    if scores.len() != labels.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Scores and labels must be same length"));
+    }
+    if let Some(i) = labels.iter().position(|&l| l > 1) {
+        return Err(Invalid::at("labels", i, labels[i] as f64, "is not 0 or 1").raise("ks_statistic"));
+    }
+    let n_pos = labels.iter().filter(|&&l| l == 1).count();
+    let n_neg = labels.len() - n_pos;
+    if n_pos == 0 || n_neg == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Both classes must be present"));
+    }
+
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[a].total_cmp(&scores[b]));
+
+    // Walk the sorted scores, only comparing the CDFs once a block of tied scores is consumed
+    let (mut cum_pos, mut cum_neg, mut ks) = (0usize, 0usize, 0.0f64);
+    for (rank, &i) in order.iter().enumerate() {
+        if labels[i] == 1 {
+            cum_pos += 1;
+        } else {
+            cum_neg += 1;
+        }
+        let end_of_tie = rank + 1 == order.len() || scores[order[rank + 1]] != scores[i];
+        if end_of_tie {
+            let gap = (cum_pos as f64 / n_pos as f64 - cum_neg as f64 / n_neg as f64).abs();
+            ks = ks.max(gap);
+        }
+    }
+    Ok(ks)
+}
+
+#[pyfunction]
+pub fn gini_from_auc(auc: f64) -> PyResult<f64> {
+    if !(0.0..=1.0).contains(&auc) {
+        return Err(pyo3::exceptions::PyValueError::new_err("AUC must be between 0 and 1"));
+    }
+    Ok(2.0 * auc - 1.0)
+}
+
+#[pyfunction]
+#[pyo3(signature = (expected_dist, actual_dist, epsilon=1e-6))]
+pub fn population_stability_index(expected_dist: Vec<f64>, actual_dist: Vec<f64>, epsilon: f64) -> PyResult<f64> {
+    if expected_dist.len() != actual_dist.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Distributions must be same length"));
+    }
+    if expected_dist.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Distributions cannot be empty"));
+    }
+    if expected_dist.iter().chain(actual_dist.iter()).any(|&x| x < 0.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Distributions cannot contain negative values"));
+    }
+    let total_expected: f64 = expected_dist.iter().sum();
+    let total_actual: f64 = actual_dist.iter().sum();
+    if total_expected <= 0.0 || total_actual <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Distributions must sum to a positive value"));
+    }
+
+    // Counts or proportions both work; empty bins are floored at epsilon to keep the log finite
+    Ok(expected_dist
+        .iter()
+        .zip(actual_dist.iter())
+        .map(|(&e, &a)| {
+            let e = (e / total_expected).max(epsilon);
+            let a = (a / total_actual).max(epsilon);
+            (a - e) * (a / e).ln()
+        })
+        .sum())
+}
+
+#[pyfunction]
+pub fn expected_loss(pd: Vec<f64>, lgd: Vec<f64>, ead: Vec<f64>) -> PyResult<Vec<f64>> {
+    if pd.len() != lgd.len() || pd.len() != ead.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("PD, LGD and EAD must be same length"));
+    }
+    for (argument, values) in [("pd", &pd), ("lgd", &lgd)] {
+        if let Some(i) = values.iter().position(|p| !(0.0..=1.0).contains(p)) {
+            return Err(Invalid::at(argument, i, values[i], "is not between 0 and 1").raise("expected_loss"));
+        }
+    }
+    if let Some(i) = ead.iter().position(|&e| !(e.is_finite() && e >= 0.0)) {
+        return Err(Invalid::at("ead", i, ead[i], "is not a non-negative exposure").raise("expected_loss"));
+    }
+    Ok(pd.iter().zip(lgd.iter()).zip(ead.iter()).map(|((p, l), e)| p * l * e).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (defaults_by_age, initial_count=None))]
+pub fn vintage_default_curve(defaults_by_age: Vec<f64>, initial_count: Option<f64>) -> PyResult<Vec<f64>> {
+    if let Some(i) = defaults_by_age.iter().position(|&d| !(d.is_finite() && d >= 0.0)) {
+        return Err(Invalid::at("defaults_by_age", i, defaults_by_age[i], "is not a non-negative count").raise("vintage_default_curve"));
+    }
+
+    // With an initial count the input is default counts, otherwise conditional default rates
+    let mut curve = Vec::with_capacity(defaults_by_age.len());
+    let mut survival = 1.0;
+    match initial_count {
+        Some(n) => {
+            if n <= 0.0 {
+                return Err(pyo3::exceptions::PyValueError::new_err("initial_count must be positive"));
+            }
+            let mut alive = n;
+            for &d in &defaults_by_age {
+                if d > alive {
+                    return Err(pyo3::exceptions::PyValueError::new_err("Defaults exceed the surviving population"));
+                }
+                alive -= d;
+                curve.push(1.0 - alive / n);
+            }
+        }
+        None => {
+            for &hazard in &defaults_by_age {
+                if hazard > 1.0 {
+                    return Err(pyo3::exceptions::PyValueError::new_err("Default rates must be between 0 and 1"));
+                }
+                survival *= 1.0 - hazard;
+                curve.push(1.0 - survival);
+            }
+        }
+    }
+    Ok(curve)
+}
+
+#[pyfunction]
+#[pyo3(signature = (delinquency_states_t0, states_t1, n_states=None))]
+pub fn roll_rate_matrix(delinquency_states_t0: Vec<usize>, states_t1: Vec<usize>, n_states: Option<usize>) -> PyResult<Vec<Vec<f64>>> {
+    if delinquency_states_t0.len() != states_t1.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("State vectors must be same length"));
+    }
+    let observed = delinquency_states_t0.iter().chain(states_t1.iter()).max().map_or(0, |&s| s + 1);
+    let k = n_states.unwrap_or(observed);
+    if k == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_states must be at least 1"));
+    }
+    if observed > k {
+        return Err(pyo3::exceptions::PyValueError::new_err("State is out of range for n_states"));
+    }
+
+    let mut matrix = vec![vec![0.0; k]; k];
+    for (&from, &to) in delinquency_states_t0.iter().zip(states_t1.iter()) {
+        matrix[from][to] += 1.0;
+    }
+    for row in matrix.iter_mut() {
+        let total: f64 = row.iter().sum();
+        if total > 0.0 {
+            row.iter_mut().for_each(|c| *c /= total);
+        }
+    }
+    Ok(matrix)
+}
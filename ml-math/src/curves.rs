@@ -0,0 +1,419 @@
+use pyo3::prelude::*;
+
+use crate::errors::Invalid;
+use crate::linalg::solve_dense;
+use crate::optimize::{golden_section, nelder_mead};
+use crate::state::{persist_methods, persist_struct};
+
+// Ordinary least squares through the normal equations for a handful of regressors
+fn small_lstsq(columns: &[Vec<f64>], y: &[f64]) -> Option<Vec<f64>> {
+    let k = columns.len();
+    let mut ata = vec![vec![0.0; k]; k];
+    let mut aty = vec![0.0; k];
+    for i in 0..k {
+        aty[i] = columns[i].iter().zip(y).map(|(a, b)| a * b).sum();
+        for j in 0..k {
+            ata[i][j] = columns[i].iter().zip(&columns[j]).map(|(a, b)| a * b).sum();
+        }
+    }
+    solve_dense(ata, aty)
+}
+
+fn ns_loadings(tau: f64, lambda: f64) -> (f64, f64) {
+    let x = tau / lambda;
+    if x < 1e-8 {
+        return (1.0, 0.0);
+    }
+    let slope = (1.0 - (-x).exp()) / x;
+    (slope, slope - (-x).exp())
+}
+
+#[pyclass(module = "fina")]
+#[derive(Clone)]
+pub struct NelsonSiegel {
+    #[pyo3(get)]
+    beta0: f64,
+    #[pyo3(get)]
+    beta1: f64,
+    #[pyo3(get)]
+    beta2: f64,
+    #[pyo3(get)]
+    lambda_: f64,
+    #[pyo3(get)]
+    rmse: f64,
+}
+
+persist_methods!(impl NelsonSiegel {
+    fn evaluate(&self, maturities: Vec<f64>) -> Vec<f64> {
+        maturities
+            .iter()
+            .map(|&t| {
+                let (l1, l2) = ns_loadings(t, self.lambda_);
+                self.beta0 + self.beta1 * l1 + self.beta2 * l2
+            })
+            .collect()
+    }
+});
+
+// Betas are linear given lambda; returns (betas, sum of squared errors)
+fn ns_betas(maturities: &[f64], yields: &[f64], lambda: f64) -> Option<(Vec<f64>, f64)> {
+    let loadings: Vec<(f64, f64)> = maturities.iter().map(|&t| ns_loadings(t, lambda)).collect();
+    let columns = vec![
+        vec![1.0; maturities.len()],
+        loadings.iter().map(|l| l.0).collect(),
+        loadings.iter().map(|l| l.1).collect(),
+    ];
+    let betas = small_lstsq(&columns, yields)?;
+    let sse = loadings
+        .iter()
+        .zip(yields)
+        .map(|(l, y)| (betas[0] + betas[1] * l.0 + betas[2] * l.1 - y).powi(2))
+        .sum();
+    Some((betas, sse))
+}
+
+#[pyfunction]
+pub fn nelson_siegel_fit(maturities: Vec<f64>, yields: Vec<f64>) -> PyResult<NelsonSiegel> {
+    if maturities.len() != yields.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Maturities and yields must be same length"));
+    }
+    if maturities.len() < 4 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Need at least four points to fit Nelson-Siegel"));
+    }
+    if let Some(i) = maturities.iter().position(|&t| !(t.is_finite() && t > 0.0)) {
+        return Err(Invalid::at("maturities", i, maturities[i], "is not positive").raise("nelson_siegel_fit"));
+    }
+
+    // Coarse grid over log(lambda), then golden-section refinement around the best point
+    let sse_at = |log_lambda: f64| ns_betas(&maturities, &yields, log_lambda.exp()).map_or(f64::INFINITY, |(_, sse)| sse);
+    let grid: Vec<f64> = (0..=60).map(|i| (0.05f64).ln() + i as f64 * ((30.0f64).ln() - (0.05f64).ln()) / 60.0).collect();
+    let best = (0..grid.len()).fold(0, |b, i| if sse_at(grid[i]) < sse_at(grid[b]) { i } else { b });
+    let lo = grid[best.saturating_sub(1)];
+    let hi = grid[(best + 1).min(grid.len() - 1)];
+    let (log_lambda, _) = golden_section(|x| Ok(sse_at(x)), lo, hi, 1e-10, 200)?;
+
+    let lambda = log_lambda.exp();
+    let (betas, sse) = ns_betas(&maturities, &yields, lambda)
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Nelson-Siegel fit is singular"))?;
+    Ok(NelsonSiegel {
+        beta0: betas[0],
+        beta1: betas[1],
+        beta2: betas[2],
+        lambda_: lambda,
+        rmse: (sse / maturities.len() as f64).sqrt(),
+    })
+}
+
+#[pyclass(module = "fina")]
+#[derive(Clone)]
+pub struct SviParams {
+    #[pyo3(get)]
+    a: f64,
+    #[pyo3(get)]
+    b: f64,
+    #[pyo3(get)]
+    rho: f64,
+    #[pyo3(get)]
+    m: f64,
+    #[pyo3(get)]
+    sigma: f64,
+    #[pyo3(get)]
+    forward: f64,
+    #[pyo3(get)]
+    t: f64,
+    #[pyo3(get)]
+    rmse: f64,
+}
+
+impl SviParams {
+    fn total_variance(&self, k: f64) -> f64 {
+        self.a + self.b * (self.rho * (k - self.m) + ((k - self.m).powi(2) + self.sigma * self.sigma).sqrt())
+    }
+}
+
+persist_methods!(impl SviParams {
+    fn implied_vol(&self, strikes: Vec<f64>) -> PyResult<Vec<f64>> {
+        if let Some(i) = strikes.iter().position(|&k| !(k.is_finite() && k > 0.0)) {
+            return Err(Invalid::at("strikes", i, strikes[i], "is not positive").raise("SviParams.implied_vol"));
+        }
+        Ok(strikes.iter().map(|&k| (self.total_variance((k / self.forward).ln()).max(0.0) / self.t).sqrt()).collect())
+    }
+});
+
+// Quasi-explicit SVI: for fixed (m, sigma) the remaining parameters are linear,
+// projected back onto b >= 0 and |rho| <= 1
+fn svi_inner(k: &[f64], w: &[f64], m: f64, sigma: f64) -> (f64, f64, f64, f64) {
+    let root: Vec<f64> = k.iter().map(|&x| ((x - m).powi(2) + sigma * sigma).sqrt()).collect();
+    let shifted: Vec<f64> = k.iter().map(|&x| x - m).collect();
+    let columns = vec![vec![1.0; k.len()], shifted.clone(), root.clone()];
+    let (mut b, mut rho) = match small_lstsq(&columns, w) {
+        Some(p) if p[2] > 1e-12 => (p[2], (p[1] / p[2]).clamp(-0.999, 0.999)),
+        _ => (1e-12, 0.0),
+    };
+    if !b.is_finite() || !rho.is_finite() {
+        b = 1e-12;
+        rho = 0.0;
+    }
+    let a = w.iter().zip(shifted.iter().zip(&root)).map(|(wi, (s, r))| wi - b * (rho * s + r)).sum::<f64>() / k.len() as f64;
+    let sse = w
+        .iter()
+        .zip(shifted.iter().zip(&root))
+        .map(|(wi, (s, r))| (a + b * (rho * s + r) - wi).powi(2))
+        .sum();
+    (a, b, rho, sse)
+}
+
+#[pyfunction]
+pub fn svi_fit(strikes: Vec<f64>, ivs: Vec<f64>, forward: f64, t: f64) -> PyResult<SviParams> {
+    if strikes.len() != ivs.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Strikes and implied vols must be same length"));
+    }
+    if strikes.len() < 5 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Need at least five quotes to fit SVI"));
+    }
+    if forward <= 0.0 || t <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("forward and t must be positive"));
+    }
+    for (argument, values) in [("strikes", &strikes), ("ivs", &ivs)] {
+        if let Some(i) = values.iter().position(|&v| !(v.is_finite() && v > 0.0)) {
+            return Err(Invalid::at(argument, i, values[i], "is not positive").raise("svi_fit"));
+        }
+    }
+
+    let k: Vec<f64> = strikes.iter().map(|&s| (s / forward).ln()).collect();
+    let w: Vec<f64> = ivs.iter().map(|&v| v * v * t).collect();
+    let k_spread = k.iter().cloned().fold(f64::NEG_INFINITY, f64::max) - k.iter().cloned().fold(f64::INFINITY, f64::min);
+
+    let objective = |p: &[f64]| -> PyResult<f64> { Ok(svi_inner(&k, &w, p[0], p[1].exp()).3) };
+    let start = [0.0, (0.1 * k_spread.max(0.01)).ln()];
+    let result = nelder_mead(objective, &start, None, 1e-12, 2000)?;
+    let (m, sigma) = (result.x[0], result.x[1].exp());
+    let (a, b, rho, sse) = svi_inner(&k, &w, m, sigma);
+
+    Ok(SviParams { a, b, rho, m, sigma, forward, t, rmse: (sse / k.len() as f64).sqrt() })
+}
+
+#[pyclass(module = "fina")]
+#[derive(Clone)]
+pub struct CubicSpline {
+    #[pyo3(get)]
+    x: Vec<f64>,
+    #[pyo3(get)]
+    y: Vec<f64>,
+    #[pyo3(get)]
+    slopes: Vec<f64>,
+}
+
+persist_methods!(impl CubicSpline {});
+
+impl CubicSpline {
+    // Cubic Hermite evaluation; outside the knots the end pieces are extended
+    pub fn eval(&self, t: f64) -> f64 {
+        let n = self.x.len();
+        let i = self.x.partition_point(|&v| v <= t).clamp(1, n - 1) - 1;
+        let h = self.x[i + 1] - self.x[i];
+        let s = (t - self.x[i]) / h;
+        let h00 = (1.0 + 2.0 * s) * (1.0 - s) * (1.0 - s);
+        let h10 = s * (1.0 - s) * (1.0 - s);
+        let h01 = s * s * (3.0 - 2.0 * s);
+        let h11 = s * s * (s - 1.0);
+        h00 * self.y[i] + h10 * h * self.slopes[i] + h01 * self.y[i + 1] + h11 * h * self.slopes[i + 1]
+    }
+}
+
+fn natural_slopes(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let h: Vec<f64> = x.windows(2).map(|w| w[1] - w[0]).collect();
+    let delta: Vec<f64> = (0..n - 1).map(|i| (y[i + 1] - y[i]) / h[i]).collect();
+    if n == 2 {
+        return vec![delta[0]; 2];
+    }
+
+    // Thomas algorithm for the second derivatives, zero at both ends
+    let mut second = vec![0.0; n];
+    let mut diag = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    for i in 1..n - 1 {
+        diag[i] = 2.0 * (h[i - 1] + h[i]);
+        rhs[i] = 6.0 * (delta[i] - delta[i - 1]);
+    }
+    for i in 2..n - 1 {
+        let factor = h[i - 1] / diag[i - 1];
+        diag[i] -= factor * h[i - 1];
+        rhs[i] -= factor * rhs[i - 1];
+    }
+    for i in (1..n - 1).rev() {
+        second[i] = (rhs[i] - h[i] * second[i + 1]) / diag[i];
+    }
+
+    let mut slopes: Vec<f64> = (0..n - 1).map(|i| delta[i] - h[i] * (2.0 * second[i] + second[i + 1]) / 6.0).collect();
+    slopes.push(delta[n - 2] + h[n - 2] * (second[n - 2] + 2.0 * second[n - 1]) / 6.0);
+    slopes
+}
+
+// Fritsch-Carlson monotone slopes, matching scipy's PchipInterpolator
+fn pchip_slopes(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let h: Vec<f64> = x.windows(2).map(|w| w[1] - w[0]).collect();
+    let delta: Vec<f64> = (0..n - 1).map(|i| (y[i + 1] - y[i]) / h[i]).collect();
+    if n == 2 {
+        return vec![delta[0]; 2];
+    }
+
+    let mut slopes = vec![0.0; n];
+    for k in 1..n - 1 {
+        if delta[k - 1] * delta[k] > 0.0 {
+            let w1 = 2.0 * h[k] + h[k - 1];
+            let w2 = h[k] + 2.0 * h[k - 1];
+            slopes[k] = (w1 + w2) / (w1 / delta[k - 1] + w2 / delta[k]);
+        }
+    }
+    let end_slope = |h0: f64, h1: f64, d0: f64, d1: f64| -> f64 {
+        let m = ((2.0 * h0 + h1) * d0 - h0 * d1) / (h0 + h1);
+        if m.signum() != d0.signum() || d0 == 0.0 {
+            0.0
+        } else if d0.signum() != d1.signum() && m.abs() > 3.0 * d0.abs() {
+            3.0 * d0
+        } else {
+            m
+        }
+    };
+    slopes[0] = end_slope(h[0], h[1], delta[0], delta[1]);
+    slopes[n - 1] = end_slope(h[n - 2], h[n - 3], delta[n - 2], delta[n - 3]);
+    slopes
+}
+
+pub fn build_spline(x: Vec<f64>, y: Vec<f64>, method: &str) -> PyResult<CubicSpline> {
+    if x.len() != y.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("x and y must be same length"));
+    }
+    if x.len() < 2 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Need at least two points"));
+    }
+    if x.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(pyo3::exceptions::PyValueError::new_err("x must be strictly increasing"));
+    }
+    let slopes = match method {
+        "natural" => natural_slopes(&x, &y),
+        "pchip" => pchip_slopes(&x, &y),
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("method must be 'natural' or 'pchip'")),
+    };
+    Ok(CubicSpline { x, y, slopes })
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, method="natural"))]
+pub fn cubic_spline_fit(x: Vec<f64>, y: Vec<f64>, method: &str) -> PyResult<CubicSpline> {
+    build_spline(x, y, method)
+}
+
+#[pyfunction]
+pub fn cubic_spline_eval(spline: PyRef<CubicSpline>, x_new: Vec<f64>) -> PyResult<Vec<f64>> {
+    Ok(x_new.iter().map(|&t| spline.eval(t)).collect())
+}
+
+// Index of the left grid point and the weight of the right one; flat beyond the ends
+pub fn bracket(grid: &[f64], v: f64) -> (usize, f64) {
+    let n = grid.len();
+    if n == 1 || v <= grid[0] {
+        return (0, 0.0);
+    }
+    if v >= grid[n - 1] {
+        return (n - 2, 1.0);
+    }
+    let i = grid.partition_point(|&g| g <= v) - 1;
+    (i, (v - grid[i]) / (grid[i + 1] - grid[i]))
+}
+
+fn validate_grid(grid_x: &[f64], grid_y: &[f64], values: &[Vec<f64>]) -> PyResult<()> {
+    if grid_x.is_empty() || grid_y.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Grids cannot be empty"));
+    }
+    if grid_x.windows(2).any(|w| w[1] <= w[0]) || grid_y.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Grids must be strictly increasing"));
+    }
+    if values.len() != grid_x.len() || values.iter().any(|row| row.len() != grid_y.len()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("values_2d must have shape (len(grid_x), len(grid_y))"));
+    }
+    Ok(())
+}
+
+fn bilinear(grid_x: &[f64], grid_y: &[f64], values: &[Vec<f64>], x: f64, y: f64) -> f64 {
+    let (i, wx) = bracket(grid_x, x);
+    let (j, wy) = bracket(grid_y, y);
+    let i1 = (i + 1).min(grid_x.len() - 1);
+    let j1 = (j + 1).min(grid_y.len() - 1);
+    let low = values[i][j] * (1.0 - wy) + values[i][j1] * wy;
+    let high = values[i1][j] * (1.0 - wy) + values[i1][j1] * wy;
+    low * (1.0 - wx) + high * wx
+}
+
+#[pyfunction]
+pub fn bilinear_interp(grid_x: Vec<f64>, grid_y: Vec<f64>, values_2d: Vec<Vec<f64>>, qx: Vec<f64>, qy: Vec<f64>) -> PyResult<Vec<f64>> {
+    validate_grid(&grid_x, &grid_y, &values_2d)?;
+    if qx.len() != qy.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("qx and qy must be same length"));
+    }
+    Ok(qx.iter().zip(qy.iter()).map(|(&x, &y)| bilinear(&grid_x, &grid_y, &values_2d, x, y)).collect())
+}
+
+#[pyclass(module = "fina")]
+#[derive(Clone)]
+pub struct VolSurface {
+    #[pyo3(get)]
+    strikes: Vec<f64>,
+    #[pyo3(get)]
+    expiries: Vec<f64>,
+    #[pyo3(get)]
+    vols: Vec<Vec<f64>>,
+}
+
+persist_methods!(impl VolSurface {
+    #[new]
+    fn new(strikes: Vec<f64>, expiries: Vec<f64>, vols: Vec<Vec<f64>>) -> PyResult<Self> {
+        // vols[i][j] is the quote for expiries[i] and strikes[j]
+        validate_grid(&expiries, &strikes, &vols)?;
+        if expiries[0] <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("Expiries must be positive"));
+        }
+        if vols.iter().flatten().any(|&v| v < 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err("Vols cannot be negative"));
+        }
+        Ok(VolSurface { strikes, expiries, vols })
+    }
+
+    fn iv(&self, strike: f64, expiry: f64) -> PyResult<f64> {
+        if expiry <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("expiry must be positive"));
+        }
+        // Linear in strike on each bracketing slice, linear in total variance across expiries
+        let (i, w) = bracket(&self.expiries, expiry);
+        let i1 = (i + 1).min(self.expiries.len() - 1);
+        let (j, wk) = bracket(&self.strikes, strike);
+        let j1 = (j + 1).min(self.strikes.len() - 1);
+        let slice = |row: &Vec<f64>| row[j] * (1.0 - wk) + row[j1] * wk;
+        let (v0, v1) = (slice(&self.vols[i]), slice(&self.vols[i1]));
+        // Flat in vol beyond the first and last expiry
+        if w <= 0.0 || i == i1 {
+            return Ok(v0);
+        }
+        if w >= 1.0 {
+            return Ok(v1);
+        }
+        let (t0, t1) = (self.expiries[i], self.expiries[i1]);
+        let total_var = v0 * v0 * t0 * (1.0 - w) + v1 * v1 * t1 * w;
+        Ok((total_var / expiry).sqrt())
+    }
+
+    fn iv_many(&self, strikes: Vec<f64>, expiries: Vec<f64>) -> PyResult<Vec<f64>> {
+        if strikes.len() != expiries.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err("Strikes and expiries must be same length"));
+        }
+        strikes.iter().zip(expiries.iter()).map(|(&k, &t)| self.iv(k, t)).collect()
+    }
+});
+
+persist_struct!(NelsonSiegel { beta0, beta1, beta2, lambda_, rmse });
+persist_struct!(SviParams { a, b, rho, m, sigma, forward, t, rmse });
+persist_struct!(CubicSpline { x, y, slopes }, check = |s| s.x.len() >= 2 && s.y.len() == s.x.len() && s.slopes.len() == s.x.len());
+persist_struct!(VolSurface { strikes, expiries, vols }, check = |s| validate_grid(&s.expiries, &s.strikes, &s.vols).is_ok());
@@ -0,0 +1,217 @@
+use pyo3::prelude::*;
+
+use crate::errors::Invalid;
+
+#[pyclass(module = "fina")]
+pub struct ValidationReport {
+    #[pyo3(get)]
+    gaps: Vec<usize>,
+    #[pyo3(get)]
+    duplicates: Vec<usize>,
+    #[pyo3(get)]
+    non_monotonic: Vec<usize>,
+    #[pyo3(get)]
+    non_positive: Vec<usize>,
+    #[pyo3(get)]
+    non_finite: Vec<usize>,
+    #[pyo3(get)]
+    stale_runs: Vec<(usize, usize)>,
+}
+
+#[pymethods]
+impl ValidationReport {
+    #[getter]
+    fn is_clean(&self) -> bool {
+        self.gaps.is_empty()
+            && self.duplicates.is_empty()
+            && self.non_monotonic.is_empty()
+            && self.non_positive.is_empty()
+            && self.non_finite.is_empty()
+            && self.stale_runs.is_empty()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ValidationReport(gaps={}, duplicates={}, non_monotonic={}, non_positive={}, non_finite={}, stale_runs={})",
+            self.gaps.len(),
+            self.duplicates.len(),
+            self.non_monotonic.len(),
+            self.non_positive.len(),
+            self.non_finite.len(),
+            self.stale_runs.len()
+        )
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (timestamps, values, expected_interval=None, gap_multiple=1.5, stale_run=5))]
+pub fn validate_series(
+    timestamps: Vec<i64>,
+    values: Vec<f64>,
+    expected_interval: Option<i64>,
+    gap_multiple: f64,
+    stale_run: usize,
+) -> PyResult<ValidationReport> {
+    if timestamps.len() != values.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Timestamps and values must be same length"));
+    }
+    if gap_multiple <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("gap_multiple must be positive"));
+    }
+    if stale_run < 2 {
+        return Err(pyo3::exceptions::PyValueError::new_err("stale_run must be at least 2"));
+    }
+
+    // Without an explicit interval, the median positive spacing defines a regular step
+    let interval = match expected_interval {
+        Some(i) if i <= 0 => return Err(pyo3::exceptions::PyValueError::new_err("expected_interval must be positive")),
+        Some(i) => Some(i),
+        None => {
+            let mut deltas: Vec<i64> = timestamps.windows(2).map(|w| w[1] - w[0]).filter(|&d| d > 0).collect();
+            if deltas.is_empty() {
+                None
+            } else {
+                let mid = deltas.len() / 2;
+                Some(*deltas.select_nth_unstable(mid).1)
+            }
+        }
+    };
+
+    let mut report = ValidationReport {
+        gaps: Vec::new(),
+        duplicates: Vec::new(),
+        non_monotonic: Vec::new(),
+        non_positive: Vec::new(),
+        non_finite: Vec::new(),
+        stale_runs: Vec::new(),
+    };
+
+    for i in 1..timestamps.len() {
+        let delta = timestamps[i] - timestamps[i - 1];
+        if delta == 0 {
+            report.duplicates.push(i);
+        } else if delta < 0 {
+            report.non_monotonic.push(i);
+        } else if let Some(step) = interval {
+            if delta as f64 > gap_multiple * step as f64 {
+                report.gaps.push(i);
+            }
+        }
+    }
+
+    let mut run_start = 0;
+    for (i, &v) in values.iter().enumerate() {
+        if !v.is_finite() {
+            report.non_finite.push(i);
+        } else if v <= 0.0 {
+            report.non_positive.push(i);
+        }
+        if i > 0 && v != values[i - 1] {
+            if i - run_start >= stale_run {
+                report.stale_runs.push((run_start, i - run_start));
+            }
+            run_start = i;
+        }
+    }
+    if values.len() - run_start >= stale_run {
+        report.stale_runs.push((run_start, values.len() - run_start));
+    }
+
+    Ok(report)
+}
+
+#[pyfunction]
+#[pyo3(signature = (timestamps, values, keep="last"))]
+pub fn dedupe_timestamps(timestamps: Vec<i64>, values: Vec<f64>, keep: &str) -> PyResult<(Vec<i64>, Vec<f64>)> {
+    if timestamps.len() != values.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Timestamps and values must be same length"));
+    }
+    let keep_last = match keep {
+        "first" => false,
+        "last" => true,
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("keep must be 'first' or 'last'")),
+    };
+
+    // Stable sort so "first" and "last" refer to the original arrival order
+    let mut order: Vec<usize> = (0..timestamps.len()).collect();
+    order.sort_by_key(|&i| timestamps[i]);
+
+    let mut out_ts: Vec<i64> = Vec::with_capacity(order.len());
+    let mut out_values: Vec<f64> = Vec::with_capacity(order.len());
+    for i in order {
+        if out_ts.last() == Some(&timestamps[i]) {
+            if keep_last {
+                *out_values.last_mut().unwrap() = values[i];
+            }
+        } else {
+            out_ts.push(timestamps[i]);
+            out_values.push(values[i]);
+        }
+    }
+    Ok((out_ts, out_values))
+}
+
+fn validate_events(function: &str, prices: &[f64], argument: &'static str, indices: &[usize], amounts: &[f64]) -> PyResult<()> {
+    if prices.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Prices cannot be empty"));
+    }
+    if indices.len() != amounts.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Event indices and amounts must be same length"));
+    }
+    if let Some(i) = indices.iter().position(|&i| i == 0 || i >= prices.len()) {
+        return Err(Invalid::at(argument, i, indices[i] as f64, "is not in 1..len(prices)").raise(function));
+    }
+    Ok(())
+}
+
+#[pyfunction]
+pub fn adjust_for_splits(prices: Vec<f64>, split_dates_idx: Vec<usize>, ratios: Vec<f64>) -> PyResult<Vec<f64>> {
+    validate_events("adjust_for_splits", &prices, "split_dates_idx", &split_dates_idx, &ratios)?;
+    if let Some(i) = ratios.iter().position(|&r| !(r.is_finite() && r > 0.0)) {
+        return Err(Invalid::at("ratios", i, ratios[i], "is not a positive ratio").raise("adjust_for_splits"));
+    }
+
+    // Every price strictly before a split date is divided by that split's ratio
+    let mut factors = vec![1.0; prices.len()];
+    for (&idx, &ratio) in split_dates_idx.iter().zip(ratios.iter()) {
+        for f in &mut factors[..idx] {
+            *f /= ratio;
+        }
+    }
+    Ok(prices.iter().zip(factors.iter()).map(|(p, f)| p * f).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (prices, ex_div_idx, amounts, method="proportional"))]
+pub fn adjust_for_dividends(prices: Vec<f64>, ex_div_idx: Vec<usize>, amounts: Vec<f64>, method: &str) -> PyResult<Vec<f64>> {
+    validate_events("adjust_for_dividends", &prices, "ex_div_idx", &ex_div_idx, &amounts)?;
+    if let Some(i) = amounts.iter().position(|&a| !(a.is_finite() && a >= 0.0)) {
+        return Err(Invalid::at("amounts", i, amounts[i], "is not a non-negative amount").raise("adjust_for_dividends"));
+    }
+
+    let mut adjusted = prices.clone();
+    match method {
+        "proportional" => {
+            for (&idx, &amount) in ex_div_idx.iter().zip(amounts.iter()) {
+                // Factor uses the unadjusted close on the day before the ex-date
+                let prev_close = prices[idx - 1];
+                if amount >= prev_close {
+                    return Err(pyo3::exceptions::PyValueError::new_err("Dividend must be smaller than the previous close"));
+                }
+                let factor = 1.0 - amount / prev_close;
+                for p in &mut adjusted[..idx] {
+                    *p *= factor;
+                }
+            }
+        }
+        "subtractive" => {
+            for (&idx, &amount) in ex_div_idx.iter().zip(amounts.iter()) {
+                for p in &mut adjusted[..idx] {
+                    *p -= amount;
+                }
+            }
+        }
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("method must be 'proportional' or 'subtractive'")),
+    }
+    Ok(adjusted)
+}
@@ -0,0 +1,122 @@
+// Columns loaded once into immutable Rust-owned memory and shared by reference: any function
+// taking a 1-D float array accepts a Column with no copy, from any number of Python threads
+
+use std::sync::Arc;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::input::{floats, ArrayLike};
+use crate::with_slice;
+
+// Read-only handle to one column; cloning it only bumps a reference count
+#[pyclass(module = "fina", frozen, sequence)]
+pub struct Column {
+    name: String,
+    pub(crate) data: Arc<[f64]>,
+}
+
+#[pymethods]
+impl Column {
+    #[getter]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn __len__(&self) -> usize {
+        self.data.len()
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<f64> {
+        let n = self.data.len() as isize;
+        let i = if index < 0 { index + n } else { index };
+        if i < 0 || i >= n {
+            return Err(pyo3::exceptions::PyIndexError::new_err("Column index out of range"));
+        }
+        Ok(self.data[i as usize])
+    }
+
+    fn tolist(&self) -> Vec<f64> {
+        self.data.to_vec()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Column(name={:?}, len={})", self.name, self.data.len())
+    }
+}
+
+// Named, equal-length float columns; immutable after construction
+#[pyclass(module = "fina", frozen)]
+pub struct Dataset {
+    columns: Vec<Py<Column>>,
+    n_rows: usize,
+}
+
+impl Dataset {
+    fn find(&self, name: &str) -> Option<&Py<Column>> {
+        self.columns.iter().find(|c| c.get().name == name)
+    }
+}
+
+#[pymethods]
+impl Dataset {
+    // From a dict of name -> array; each array is copied exactly once, here
+    #[new]
+    fn new(py: Python<'_>, columns: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut loaded = Vec::with_capacity(columns.len());
+        let mut n_rows = None;
+        for (name, values) in columns.iter() {
+            let name: String = name.extract()?;
+            let values: ArrayLike = values.extract()?;
+            let data: Arc<[f64]> = with_slice!(values, values => floats(values).collect());
+            if *n_rows.get_or_insert(data.len()) != data.len() {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Column {:?} has {} rows but earlier columns have {}",
+                    name,
+                    data.len(),
+                    n_rows.unwrap_or(0)
+                )));
+            }
+            loaded.push(Py::new(py, Column { name, data })?);
+        }
+        Ok(Dataset { columns: loaded, n_rows: n_rows.unwrap_or(0) })
+    }
+
+    fn __getitem__(&self, py: Python<'_>, name: &str) -> PyResult<Py<Column>> {
+        self.find(name)
+            .map(|c| c.clone_ref(py))
+            .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(name.to_string()))
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        self.columns.len()
+    }
+
+    #[getter]
+    fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    #[getter]
+    fn columns(&self) -> Vec<String> {
+        self.columns.iter().map(|c| c.get().name.clone()).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Dataset(columns={:?}, n_rows={})", self.columns(), self.n_rows)
+    }
+}
+
+// Registering Column as a collections.abc.Sequence lets functions that take a plain list of
+// floats accept it too (those copy it, unlike the array functions)
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Column>()?;
+    m.add_class::<Dataset>()?;
+    let sequence = m.py().import("collections.abc")?.getattr("Sequence")?;
+    sequence.call_method1("register", (m.py().get_type::<Column>(),))?;
+    Ok(())
+}
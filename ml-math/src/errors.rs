@@ -0,0 +1,95 @@
+use pyo3::create_exception;
+use pyo3::prelude::*;
+
+use crate::input::{floats, Element};
+
+create_exception!(
+    fina,
+    InputError,
+    pyo3::exceptions::PyValueError,
+    "Invalid input; the function, argument, index and value attributes locate the offending element"
+);
+
+// A bad input described without touching Python, so detached slice kernels can report it
+pub struct Invalid {
+    argument: &'static str,
+    index: Option<usize>,
+    value: Option<f64>,
+    reason: String,
+}
+
+impl Invalid {
+    pub fn new(argument: &'static str, reason: impl Into<String>) -> Self {
+        Invalid { argument, index: None, value: None, reason: reason.into() }
+    }
+
+    pub fn at(argument: &'static str, index: usize, value: f64, reason: impl Into<String>) -> Self {
+        Invalid { argument, index: Some(index), value: Some(value), reason: reason.into() }
+    }
+
+    // e.g. "cross_entropy: pred[1371] = -0.002 is not positive"
+    pub fn raise(self, function: &str) -> PyErr {
+        let location = match (self.index, self.value) {
+            (Some(i), Some(v)) => format!("{}[{}] = {}", self.argument, i, v),
+            _ => self.argument.to_string(),
+        };
+        let err = InputError::new_err(format!("{}: {} {}", function, location, self.reason));
+        Python::attach(|py| {
+            let value = err.value(py);
+            let attrs = [
+                value.setattr("function", function),
+                value.setattr("argument", self.argument),
+                value.setattr("index", self.index),
+                value.setattr("value", self.value),
+            ];
+            attrs.into_iter().collect::<PyResult<()>>().map(|_| err)
+        })
+        .unwrap_or_else(|e| e)
+    }
+}
+
+// Location of the first NaN or infinity in `values`
+pub fn check_finite(function: &str, argument: &'static str, values: &[f64]) -> PyResult<()> {
+    all_finite(argument, values).map_err(|e| e.raise(function))
+}
+
+pub fn all_finite<T: Element>(argument: &'static str, values: &[T]) -> Result<(), Invalid> {
+    match floats(values).enumerate().find(|(_, v)| !v.is_finite()) {
+        Some((i, v)) => Err(Invalid::at(argument, i, v, "is not finite")),
+        None => Ok(()),
+    }
+}
+
+// Length check shared by the two-array functions
+pub fn same_length(a_name: &'static str, a: usize, b_name: &'static str, b: usize) -> Result<(), Invalid> {
+    if a != b {
+        return Err(Invalid::new(b_name, format!("has {} elements but {} has {}", b, a_name, a)));
+    }
+    if a == 0 {
+        return Err(Invalid::new(a_name, "cannot be empty"));
+    }
+    Ok(())
+}
+
+// How the core functions treat questionable but recoverable input: "strict" raises,
+// "lenient" clamps or substitutes a neutral result so a pipeline keeps running
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    Strict,
+    Lenient,
+}
+
+impl<'py> FromPyObject<'_, 'py> for Validation {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'_, 'py, PyAny>) -> PyResult<Self> {
+        match obj.extract::<String>()?.as_str() {
+            "strict" => Ok(Validation::Strict),
+            "lenient" => Ok(Validation::Lenient),
+            other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "validate must be 'strict' or 'lenient', got '{}'",
+                other
+            ))),
+        }
+    }
+}
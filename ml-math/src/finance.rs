@@ -0,0 +1,229 @@
+use pyo3::prelude::*;
+
+use crate::errors::Invalid;
+use crate::rng::Rng;
+
+#[pyfunction]
+pub fn convert_series(amounts: Vec<f64>, fx_rates: Vec<f64>) -> PyResult<Vec<f64>> {
+    if amounts.len() != fx_rates.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Amounts and FX rates must be same length"));
+    }
+    if let Some(i) = fx_rates.iter().position(|&r| !(r.is_finite() && r > 0.0)) {
+        return Err(Invalid::at("fx_rates", i, fx_rates[i], "is not a positive rate").raise("convert_series"));
+    }
+    Ok(amounts.iter().zip(fx_rates.iter()).map(|(a, r)| a * r).collect())
+}
+
+#[pyfunction]
+pub fn cross_rate(base_quote_a: f64, base_quote_b: f64) -> PyResult<f64> {
+    // A/Q divided by B/Q gives A/B
+    if base_quote_a <= 0.0 || base_quote_b <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("FX rates must be positive"));
+    }
+    Ok(base_quote_a / base_quote_b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (local_returns, fx_returns, hedge_ratio=0.0))]
+pub fn hedged_returns(local_returns: Vec<f64>, fx_returns: Vec<f64>, hedge_ratio: f64) -> PyResult<Vec<f64>> {
+    if local_returns.len() != fx_returns.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Return series must be same length"));
+    }
+    if !hedge_ratio.is_finite() {
+        return Err(pyo3::exceptions::PyValueError::new_err("hedge_ratio must be finite"));
+    }
+    // Unhedged base-currency return, less the hedged fraction of the FX move
+    Ok(local_returns
+        .iter()
+        .zip(fx_returns.iter())
+        .map(|(rl, rfx)| (1.0 + rl) * (1.0 + rfx) - 1.0 - hedge_ratio * rfx)
+        .collect())
+}
+
+#[pyfunction]
+pub fn real_returns(nominal_returns: Vec<f64>, inflation_rates: Vec<f64>) -> PyResult<Vec<f64>> {
+    if nominal_returns.len() != inflation_rates.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Return and inflation series must be same length"));
+    }
+    if let Some(i) = inflation_rates.iter().position(|&r| !(r.is_finite() && r > -1.0)) {
+        return Err(Invalid::at("inflation_rates", i, inflation_rates[i], "is not greater than -1").raise("real_returns"));
+    }
+    // Fisher relation, not the nominal-minus-inflation approximation
+    Ok(nominal_returns.iter().zip(inflation_rates.iter()).map(|(r, i)| (1.0 + r) / (1.0 + i) - 1.0).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, cpi_index, base_index=None))]
+pub fn deflate_series(values: Vec<f64>, cpi_index: Vec<f64>, base_index: Option<f64>) -> PyResult<Vec<f64>> {
+    if values.len() != cpi_index.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Values and CPI index must be same length"));
+    }
+    if values.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+    }
+    if let Some(i) = cpi_index.iter().position(|&c| !(c.is_finite() && c > 0.0)) {
+        return Err(Invalid::at("cpi_index", i, cpi_index[i], "is not positive").raise("deflate_series"));
+    }
+    // Defaults to expressing everything in the money of the last period
+    let base = base_index.unwrap_or(cpi_index[cpi_index.len() - 1]);
+    if base <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("base_index must be positive"));
+    }
+    Ok(values.iter().zip(cpi_index.iter()).map(|(v, c)| v * base / c).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (lots_qty, lots_price, sells_qty, sells_price, method="FIFO"))]
+pub fn realized_gains(
+    lots_qty: Vec<f64>,
+    lots_price: Vec<f64>,
+    sells_qty: Vec<f64>,
+    sells_price: Vec<f64>,
+    method: &str,
+) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    if lots_qty.len() != lots_price.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Lot quantities and prices must be same length"));
+    }
+    if sells_qty.len() != sells_price.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Sell quantities and prices must be same length"));
+    }
+    if lots_qty.iter().chain(sells_qty.iter()).any(|&q| q < 0.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Quantities cannot be negative"));
+    }
+
+    // Order in which lots are relieved; ties in HIFO fall back to purchase order
+    let mut order: Vec<usize> = (0..lots_qty.len()).collect();
+    match method {
+        "FIFO" => {}
+        "LIFO" => order.reverse(),
+        "HIFO" => order.sort_by(|&a, &b| lots_price[b].total_cmp(&lots_price[a])),
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("method must be 'FIFO', 'LIFO' or 'HIFO'")),
+    }
+
+    let mut remaining = lots_qty.clone();
+    let mut gains = Vec::with_capacity(sells_qty.len());
+    let tolerance = 1e-9;
+    for (&qty, &price) in sells_qty.iter().zip(sells_price.iter()) {
+        let mut to_sell = qty;
+        let mut gain = 0.0;
+        for &lot in &order {
+            if to_sell <= tolerance {
+                break;
+            }
+            let take = remaining[lot].min(to_sell);
+            if take <= 0.0 {
+                continue;
+            }
+            gain += take * (price - lots_price[lot]);
+            remaining[lot] -= take;
+            to_sell -= take;
+        }
+        if to_sell > tolerance {
+            return Err(pyo3::exceptions::PyValueError::new_err("Sell quantity exceeds remaining lots"));
+        }
+        gains.push(gain);
+    }
+
+    let (remaining_qty, remaining_price): (Vec<f64>, Vec<f64>) = remaining
+        .iter()
+        .zip(lots_price.iter())
+        .filter(|(&q, _)| q > tolerance)
+        .map(|(&q, &p)| (q, p))
+        .unzip();
+    Ok((gains, remaining_qty, remaining_price))
+}
+
+#[pyclass(module = "fina")]
+pub struct ProjectionResult {
+    #[pyo3(get)]
+    percentiles: Vec<f64>,
+    #[pyo3(get)]
+    bands: Vec<Vec<f64>>,
+    #[pyo3(get)]
+    success_probability: f64,
+}
+
+// Linear interpolation between closest ranks on already sorted data
+fn sorted_quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+#[pyfunction]
+#[pyo3(signature = (initial, contributions, expected_return, vol, years, n_paths, withdrawal_rate=0.0, seed=None, percentiles=vec![5.0, 25.0, 50.0, 75.0, 95.0]))]
+#[allow(clippy::too_many_arguments)]
+pub fn monte_carlo_projection(
+    initial: f64,
+    contributions: f64,
+    expected_return: f64,
+    vol: f64,
+    years: usize,
+    n_paths: usize,
+    withdrawal_rate: f64,
+    seed: Option<u64>,
+    percentiles: Vec<f64>,
+) -> PyResult<ProjectionResult> {
+    if initial < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Initial balance cannot be negative"));
+    }
+    if vol < 0.0 || withdrawal_rate < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("vol and withdrawal_rate cannot be negative"));
+    }
+    if expected_return <= -1.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("expected_return must be greater than -1"));
+    }
+    if years == 0 || n_paths == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("years and n_paths must be positive"));
+    }
+    if percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Percentiles must be between 0 and 100"));
+    }
+
+    let mut rng = Rng::from_seed(seed);
+    // Lognormal annual growth with E[1 + R] = 1 + expected_return
+    let drift = libm::log(1.0 + expected_return) - 0.5 * vol * vol;
+    let withdrawal = withdrawal_rate * initial;
+    let steps = years + 1;
+    let mut balances = vec![0.0; n_paths * steps];
+    let mut successes = 0usize;
+
+    for path in balances.chunks_mut(steps) {
+        let mut balance = initial;
+        let mut depleted = false;
+        path[0] = balance;
+        for value in path.iter_mut().skip(1) {
+            if !depleted {
+                let growth = libm::exp(drift + vol * rng.normal());
+                balance = balance * growth + contributions - withdrawal;
+                if balance <= 0.0 {
+                    balance = 0.0;
+                    depleted = true;
+                }
+            }
+            *value = balance;
+        }
+        if !depleted {
+            successes += 1;
+        }
+    }
+
+    let mut bands = vec![Vec::with_capacity(steps); percentiles.len()];
+    let mut column = vec![0.0; n_paths];
+    for year in 0..steps {
+        for (p, c) in column.iter_mut().enumerate() {
+            *c = balances[p * steps + year];
+        }
+        column.sort_by(|a, b| a.total_cmp(b));
+        for (band, &pct) in bands.iter_mut().zip(percentiles.iter()) {
+            band.push(sorted_quantile(&column, pct / 100.0));
+        }
+    }
+
+    Ok(ProjectionResult {
+        percentiles,
+        bands,
+        success_probability: successes as f64 / n_paths as f64,
+    })
+}
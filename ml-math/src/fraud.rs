@@ -0,0 +1,61 @@
+use pyo3::prelude::*;
+
+#[pyclass(module = "fina")]
+pub struct BenfordResult {
+    #[pyo3(get)]
+    observed: Vec<f64>,
+    #[pyo3(get)]
+    expected: Vec<f64>,
+    #[pyo3(get)]
+    chi_square: f64,
+    #[pyo3(get)]
+    p_value: f64,
+    #[pyo3(get)]
+    n: usize,
+}
+
+fn leading_digit(value: f64) -> Option<usize> {
+    let v = value.abs();
+    if !v.is_finite() || v == 0.0 {
+        return None;
+    }
+    let mut mantissa = v / 10f64.powf(v.log10().floor());
+    // log10 can be off by one ulp near exact powers of ten
+    if mantissa >= 10.0 {
+        mantissa /= 10.0;
+    } else if mantissa < 1.0 {
+        mantissa *= 10.0;
+    }
+    Some((mantissa.floor() as usize).clamp(1, 9))
+}
+
+#[pyfunction]
+pub fn benford_test(values: Vec<f64>) -> PyResult<BenfordResult> {
+    let mut counts = [0usize; 9];
+    for &v in &values {
+        if let Some(d) = leading_digit(v) {
+            counts[d - 1] += 1;
+        }
+    }
+    let n: usize = counts.iter().sum();
+    if n == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("No non-zero finite values to test"));
+    }
+
+    let expected: Vec<f64> = (1..=9).map(|d| (1.0 + 1.0 / d as f64).log10()).collect();
+    let observed: Vec<f64> = counts.iter().map(|&c| c as f64 / n as f64).collect();
+    let chi_square: f64 = counts
+        .iter()
+        .zip(expected.iter())
+        .map(|(&c, &p)| {
+            let e = p * n as f64;
+            (c as f64 - e).powi(2) / e
+        })
+        .sum();
+
+    // Survival function of chi-square with 8 degrees of freedom has a closed form
+    let half = chi_square / 2.0;
+    let p_value = (-half).exp() * (1.0 + half + half * half / 2.0 + half.powi(3) / 6.0);
+
+    Ok(BenfordResult { observed, expected, chi_square, p_value, n })
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+use crate::rng::{splitmix64, Rng};
+use crate::state::{self, persist_methods, Persist, Reader, Writer};
+
+// MurmurHash3 x86 32-bit, the same hash scikit-learn's FeatureHasher uses
+pub fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let mut h = seed;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (i, &b) in tail.iter().enumerate() {
+            k |= (b as u32) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+    }
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h
+}
+
+// 64-bit hash from two independently seeded MurmurHash3 passes
+pub fn hash64(data: &[u8], seed: u64) -> u64 {
+    let hi = murmur3_32(data, seed as u32) as u64;
+    let lo = murmur3_32(data, (seed >> 32) as u32 ^ 0x9e37_79b9) as u64;
+    (hi << 32) | lo
+}
+
+// Signed hashing trick: the hash picks the bucket, its sign bit picks +1 or -1
+fn hash_tokens<'a, I>(tokens: I, n_features: usize, seed: u32) -> (Vec<usize>, Vec<f64>)
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut buckets: HashMap<usize, f64> = HashMap::new();
+    for token in tokens {
+        let h = murmur3_32(token, seed) as i32;
+        let index = (h.unsigned_abs() as usize) % n_features;
+        let sign = if h >= 0 { 1.0 } else { -1.0 };
+        *buckets.entry(index).or_insert(0.0) += sign;
+    }
+    let mut pairs: Vec<(usize, f64)> = buckets.into_iter().filter(|&(_, v)| v != 0.0).collect();
+    pairs.sort_unstable_by_key(|&(i, _)| i);
+    pairs.into_iter().unzip()
+}
+
+#[pyfunction]
+#[pyo3(signature = (strings, n_features, seed=0))]
+pub fn feature_hash(strings: Vec<String>, n_features: usize, seed: u32) -> PyResult<(Vec<usize>, Vec<f64>)> {
+    if n_features == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_features must be positive"));
+    }
+    Ok(hash_tokens(strings.iter().map(|s| s.as_bytes()), n_features, seed))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, n, n_features, seed=0))]
+pub fn ngram_hash(text: &str, n: usize, n_features: usize, seed: u32) -> PyResult<(Vec<usize>, Vec<f64>)> {
+    if n == 0 || n_features == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n and n_features must be positive"));
+    }
+    // Character n-grams, sliced on char boundaries so multi-byte text is handled
+    let boundaries: Vec<usize> = text.char_indices().map(|(i, _)| i).chain(std::iter::once(text.len())).collect();
+    let grams = boundaries.windows(n + 1).map(|w| &text.as_bytes()[w[0]..w[n]]);
+    Ok(hash_tokens(grams, n_features, seed))
+}
+
+// Universal hashes (a x + b) mod p over the Mersenne prime 2^61 - 1
+const MERSENNE_61: u64 = (1 << 61) - 1;
+
+// One MinHash value per permutation: the minimum of (a_k x + b_k) mod p over the token hashes.
+// The permutations depend only on the seed, so signatures are comparable across calls that share it
+#[pyfunction]
+#[pyo3(signature = (token_hashes, n_perm=128, seed=0))]
+pub fn minhash_signature(token_hashes: Vec<i64>, n_perm: usize, seed: u64) -> PyResult<Vec<u64>> {
+    if token_hashes.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("token_hashes cannot be empty"));
+    }
+    if n_perm == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_perm must be positive"));
+    }
+    let mut rng = Rng::new(seed);
+    let tokens: Vec<u128> = token_hashes.iter().map(|&t| (t as u64 % MERSENNE_61) as u128).collect();
+    Ok((0..n_perm)
+        .map(|_| {
+            let a = (1 + rng.next_u64() % (MERSENNE_61 - 1)) as u128;
+            let b = (rng.next_u64() % MERSENNE_61) as u128;
+            tokens.iter().map(|&x| ((a * x + b) % MERSENNE_61 as u128) as u64).min().unwrap()
+        })
+        .collect())
+}
+
+// Banded LSH over MinHash signatures: items sharing every value in at least one band of
+// rows_per_band consecutive values become candidates of each other
+#[pyclass(module = "fina")]
+pub struct LSHIndex {
+    n_bands: usize,
+    rows_per_band: usize,
+    n_items: usize,
+    buckets: HashMap<u64, Vec<i64>>,
+}
+
+impl LSHIndex {
+    fn band_keys(&self, signature: &[u64]) -> PyResult<Vec<u64>> {
+        if signature.len() != self.n_bands * self.rows_per_band {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "signature must have n_bands * rows_per_band = {} values, got {}",
+                self.n_bands * self.rows_per_band,
+                signature.len()
+            )));
+        }
+        // The band number is mixed in so equal values in different bands do not collide
+        Ok(signature
+            .chunks(self.rows_per_band)
+            .enumerate()
+            .map(|(band, values)| {
+                let mut h = band as u64;
+                for &v in values {
+                    h ^= v;
+                    h = splitmix64(&mut h);
+                }
+                h
+            })
+            .collect())
+    }
+}
+
+persist_methods!(impl LSHIndex {
+    #[new]
+    #[pyo3(signature = (n_bands=32, rows_per_band=4))]
+    fn new(n_bands: usize, rows_per_band: usize) -> PyResult<Self> {
+        if n_bands == 0 || rows_per_band == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("n_bands and rows_per_band must be positive"));
+        }
+        Ok(LSHIndex { n_bands, rows_per_band, n_items: 0, buckets: HashMap::new() })
+    }
+
+    fn insert(&mut self, id: i64, signature: Vec<u64>) -> PyResult<()> {
+        for key in self.band_keys(&signature)? {
+            self.buckets.entry(key).or_default().push(id);
+        }
+        self.n_items += 1;
+        Ok(())
+    }
+
+    // Candidate ids in ascending order, each once
+    fn query(&self, signature: Vec<u64>) -> PyResult<Vec<i64>> {
+        let mut ids: Vec<i64> = self
+            .band_keys(&signature)?
+            .iter()
+            .filter_map(|key| self.buckets.get(key))
+            .flatten()
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    fn __len__(&self) -> usize {
+        self.n_items
+    }
+
+    #[getter]
+    fn n_bands(&self) -> usize {
+        self.n_bands
+    }
+
+    #[getter]
+    fn rows_per_band(&self) -> usize {
+        self.rows_per_band
+    }
+});
+
+// Buckets are written sorted by key so equal indexes serialize to equal bytes
+impl Persist for LSHIndex {
+    const NAME: &'static str = "LSHIndex";
+
+    fn save(&self, w: &mut Writer) {
+        self.n_bands.save(w);
+        self.rows_per_band.save(w);
+        self.n_items.save(w);
+        let mut buckets: Vec<(u64, Vec<i64>)> = self.buckets.iter().map(|(k, v)| (*k, v.clone())).collect();
+        buckets.sort_unstable_by_key(|b| b.0);
+        buckets.save(w);
+    }
+
+    fn load(r: &mut Reader) -> PyResult<Self> {
+        let n_bands = usize::load(r)?;
+        let rows_per_band = usize::load(r)?;
+        let n_items = usize::load(r)?;
+        let buckets = Vec::<(u64, Vec<i64>)>::load(r)?;
+        if n_bands == 0 || rows_per_band == 0 {
+            return Err(state::corrupt());
+        }
+        Ok(LSHIndex { n_bands, rows_per_band, n_items, buckets: buckets.into_iter().collect() })
+    }
+}
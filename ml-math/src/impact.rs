@@ -0,0 +1,97 @@
+use pyo3::prelude::*;
+
+// Square-root law: expected impact as a fraction of price, coefficient * vol * sqrt(quantity / adv)
+#[pyfunction]
+#[pyo3(signature = (quantity, adv, vol, coefficient=1.0))]
+pub fn sqrt_impact(quantity: f64, adv: f64, vol: f64, coefficient: f64) -> PyResult<f64> {
+    if adv <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("adv must be positive"));
+    }
+    if vol < 0.0 || coefficient < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("vol and coefficient cannot be negative"));
+    }
+    Ok(coefficient * vol * (quantity.abs() / adv).sqrt())
+}
+
+// Discrete Almgren-Chriss optimal liquidation; impact_params is (temporary eta, permanent gamma) with linear
+// impact, and the result is (holdings at each of the n_steps + 1 times, trades per interval)
+#[pyfunction]
+#[pyo3(signature = (quantity, horizon, risk_aversion, vol, impact_params, n_steps=20))]
+pub fn almgren_chriss_schedule(
+    quantity: f64,
+    horizon: f64,
+    risk_aversion: f64,
+    vol: f64,
+    impact_params: (f64, f64),
+    n_steps: usize,
+) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let (eta, gamma) = impact_params;
+    if horizon <= 0.0 || n_steps == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("horizon and n_steps must be positive"));
+    }
+    if risk_aversion < 0.0 || vol < 0.0 || gamma < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "risk_aversion, vol and permanent impact cannot be negative",
+        ));
+    }
+    let tau = horizon / n_steps as f64;
+    let eta_tilde = eta - 0.5 * gamma * tau;
+    if eta_tilde <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "Temporary impact must exceed half the permanent impact per step",
+        ));
+    }
+
+    let holdings = liquidation_holdings(quantity, horizon, risk_aversion * vol * vol / eta_tilde, n_steps);
+    let trades = holdings.windows(2).map(|w| w[0] - w[1]).collect();
+    Ok((holdings, trades))
+}
+
+// kappa solves 2 (cosh(kappa tau) - 1) / tau^2 = lambda sigma^2 / eta_tilde; zero urgency is TWAP
+fn liquidation_holdings(quantity: f64, horizon: f64, kappa_tilde_sq: f64, n_steps: usize) -> Vec<f64> {
+    let tau = horizon / n_steps as f64;
+    let kappa = (0.5 * kappa_tilde_sq * tau * tau + 1.0).acosh() / tau;
+    (0..=n_steps)
+        .map(|j| {
+            let remaining = horizon - j as f64 * tau;
+            if kappa * horizon < 1e-12 {
+                quantity * remaining / horizon
+            } else {
+                // sinh(kappa r) / sinh(kappa T) rewritten so neither sinh overflows at high urgency
+                quantity * (kappa * (remaining - horizon)).exp() * (-2.0 * kappa * remaining).exp_m1()
+                    / (-2.0 * kappa * horizon).exp_m1()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn almgren_chriss_matches_sinh_form() {
+        let kappa_tilde_sq = 1e-4 * 0.09 / 1e-2;
+        let holdings = liquidation_holdings(1000.0, 1.0, kappa_tilde_sq, 10);
+        let kappa = (0.5 * kappa_tilde_sq * 0.01 + 1.0_f64).acosh() / 0.1;
+        for (j, h) in holdings.iter().enumerate() {
+            let expected = 1000.0 * (kappa * (1.0 - j as f64 * 0.1)).sinh() / kappa.sinh();
+            assert!((h - expected).abs() < 1e-9, "{} vs {}", h, expected);
+        }
+    }
+
+    #[test]
+    fn almgren_chriss_large_kappa_stays_finite() {
+        // Inputs of almgren_chriss_schedule(1e6, 1.0, 1e-2, 0.3, (1e-9, 0.0), 1000)
+        let holdings = liquidation_holdings(1e6, 1.0, 1e-2 * 0.09 / 1e-9, 1000);
+        let trades: Vec<f64> = holdings.windows(2).map(|w| w[0] - w[1]).collect();
+        assert!(holdings.iter().chain(&trades).all(|v| v.is_finite()));
+        assert_eq!(holdings[0], 1e6);
+        assert_eq!(holdings[1000], 0.0);
+        // Far from the end the schedule decays as exp(-kappa t)
+        let kappa = (0.5 * 9e5 * 1e-6 + 1.0_f64).acosh() / 1e-3;
+        assert!((holdings[1] / 1e6 - (-kappa * 1e-3).exp()).abs() < 1e-12);
+        assert!(trades.windows(2).all(|w| w[0] >= w[1]));
+        assert!((trades.iter().sum::<f64>() - 1e6).abs() < 1e-6);
+    }
+}
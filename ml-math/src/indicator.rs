@@ -0,0 +1,513 @@
+// A small expression language for custom indicators, e.g. "ema(close, 20) - sma(close, 50)".
+// Expressions are parsed once into a tree and evaluated natively over whole series. Every
+// intermediate series keeps the input length: windowed functions emit NaN until their window
+// (and that of their argument) has filled, so arithmetic between them lines up by index
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+use crate::errors::check_finite;
+use crate::input::{detach_large, floats, ArrayLike};
+use crate::plugins::{self, Kernel};
+use crate::rolling::{window_extreme, window_stds, window_sums};
+use crate::with_slice;
+
+#[derive(Clone, Copy)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Clone, Copy)]
+enum Func {
+    Abs,
+    Sqrt,
+    Log,
+    Exp,
+}
+
+#[derive(Clone, Copy)]
+enum Window {
+    Sma,
+    Ema,
+    Std,
+    Sum,
+    Min,
+    Max,
+    Lag,
+    Diff,
+}
+
+// Function names the parser accepts
+const PRIMITIVES: &[(&str, Primitive)] = &[
+    ("sma", Primitive::Window(Window::Sma)),
+    ("ema", Primitive::Window(Window::Ema)),
+    ("std", Primitive::Window(Window::Std)),
+    ("sum", Primitive::Window(Window::Sum)),
+    ("min", Primitive::Window(Window::Min)),
+    ("max", Primitive::Window(Window::Max)),
+    ("lag", Primitive::Window(Window::Lag)),
+    ("diff", Primitive::Window(Window::Diff)),
+    ("abs", Primitive::Func(Func::Abs)),
+    ("sqrt", Primitive::Func(Func::Sqrt)),
+    ("log", Primitive::Func(Func::Log)),
+    ("exp", Primitive::Func(Func::Exp)),
+];
+
+#[derive(Clone, Copy)]
+enum Primitive {
+    Func(Func),
+    Window(Window),
+}
+
+pub fn is_primitive(name: &str) -> bool {
+    PRIMITIVES.iter().any(|(n, _)| *n == name)
+}
+
+enum Expr {
+    Const(f64),
+    Var(usize),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Func(Func, Box<Expr>),
+    Window(Window, Box<Expr>, usize),
+    // A kernel from the plugin registry, resolved when the expression is compiled
+    Kernel(String, Kernel, Box<Expr>, Vec<f64>),
+}
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Symbol(char),
+    End,
+}
+
+// Kept free of PyErr so the parser runs without Python; raised as ValueError by compile_indicator
+struct SyntaxError {
+    column: usize,
+    message: String,
+}
+
+impl SyntaxError {
+    fn raise(self) -> PyErr {
+        pyo3::exceptions::PyValueError::new_err(format!("compile_indicator: {} at column {}", self.message, self.column + 1))
+    }
+}
+
+fn syntax_error(column: usize, message: impl std::fmt::Display) -> SyntaxError {
+    SyntaxError { column, message: message.to_string() }
+}
+
+// Tokens paired with the column they start at
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, SyntaxError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.' || ((chars[i] == '+' || chars[i] == '-') && matches!(chars[i - 1], 'e' | 'E'))) {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text.parse::<f64>().map_err(|_| syntax_error(start, format!("invalid number {:?}", text)))?;
+            tokens.push((Token::Number(value), start));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+        } else if "+-*/(),".contains(c) {
+            tokens.push((Token::Symbol(c), start));
+            i += 1;
+        } else {
+            return Err(syntax_error(start, format!("unexpected character {:?}", c)));
+        }
+    }
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+// Recursive descent: expr = term (+|- term)*, term = unary (*|/ unary)*, unary = -unary | atom
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    variables: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn column(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if token != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), SyntaxError> {
+        if *self.peek() != Token::Symbol(symbol) {
+            return Err(syntax_error(self.column(), format!("expected '{}'", symbol)));
+        }
+        self.next();
+        Ok(())
+    }
+
+    fn expr(&mut self) -> Result<Expr, SyntaxError> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Token::Symbol('+') => Op::Add,
+                Token::Symbol('-') => Op::Sub,
+                _ => return Ok(lhs),
+            };
+            self.next();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, SyntaxError> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Token::Symbol('*') => Op::Mul,
+                Token::Symbol('/') => Op::Div,
+                _ => return Ok(lhs),
+            };
+            self.next();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, SyntaxError> {
+        if *self.peek() == Token::Symbol('-') {
+            self.next();
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, SyntaxError> {
+        let column = self.column();
+        match self.next() {
+            Token::Number(value) => Ok(Expr::Const(value)),
+            Token::Symbol('(') => {
+                let inner = self.expr()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Token::Ident(name) if *self.peek() == Token::Symbol('(') => self.call(&name, column),
+            Token::Ident(name) => {
+                let index = match self.variables.iter().position(|v| *v == name) {
+                    Some(index) => index,
+                    None => {
+                        self.variables.push(name);
+                        self.variables.len() - 1
+                    }
+                };
+                Ok(Expr::Var(index))
+            }
+            Token::End => Err(syntax_error(column, "unexpected end of expression")),
+            Token::Symbol(c) => Err(syntax_error(column, format!("unexpected '{}'", c))),
+        }
+    }
+
+    fn call(&mut self, name: &str, column: usize) -> Result<Expr, SyntaxError> {
+        let primitive = match PRIMITIVES.iter().find(|(n, _)| *n == name) {
+            Some((_, primitive)) => *primitive,
+            None => match plugins::lookup(name) {
+                Some(kernel) => return self.kernel_call(name, kernel),
+                None => return Err(syntax_error(column, format!("unknown function {:?}", name))),
+            },
+        };
+        self.expect('(')?;
+        let arg = self.expr()?;
+        let expr = match primitive {
+            Primitive::Func(func) => Expr::Func(func, Box::new(arg)),
+            Primitive::Window(window) => {
+                self.expect(',')?;
+                let column = self.column();
+                let length = match self.next() {
+                    Token::Number(v) if v >= 1.0 && v.fract() == 0.0 => v as usize,
+                    _ => return Err(syntax_error(column, format!("{}() needs a positive whole-number window", name))),
+                };
+                Expr::Window(window, Box::new(arg), length)
+            }
+        };
+        self.expect(')')?;
+        Ok(expr)
+    }
+
+    // name(series, p1, ..., pk) with the kernel's parameters given as numbers
+    fn kernel_call(&mut self, name: &str, kernel: Kernel) -> Result<Expr, SyntaxError> {
+        self.expect('(')?;
+        let arg = self.expr()?;
+        let mut params = Vec::with_capacity(kernel.n_params);
+        for _ in 0..kernel.n_params {
+            self.expect(',')?;
+            let column = self.column();
+            let sign = if *self.peek() == Token::Symbol('-') {
+                self.next();
+                -1.0
+            } else {
+                1.0
+            };
+            match self.next() {
+                Token::Number(v) => params.push(sign * v),
+                _ => {
+                    return Err(syntax_error(column, format!("{}() takes a series and {} numeric parameters", name, kernel.n_params)))
+                }
+            }
+        }
+        if *self.peek() != Token::Symbol(')') {
+            return Err(syntax_error(self.column(), format!("{}() takes a series and {} numeric parameters", name, kernel.n_params)));
+        }
+        self.next();
+        Ok(Expr::Kernel(name.to_string(), kernel, Box::new(arg), params))
+    }
+}
+
+// Runs a kernel that returns len - window + 1 values over each stretch of finite values, so a NaN
+// or infinity (a warm-up NaN, or a mid-series one such as log of a negative) blanks only the
+// windows containing it, and the kernel's running state starts afresh once it has left the window
+fn windowed(values: &[f64], window: usize, kernel: impl Fn(&[f64]) -> Vec<f64>) -> Vec<f64> {
+    let n = values.len();
+    let mut out = vec![f64::NAN; n];
+    let mut start = 0;
+    while start < n {
+        let end = values[start..].iter().position(|v| !v.is_finite()).map_or(n, |p| start + p);
+        if end - start >= window {
+            out[start + window - 1..end].copy_from_slice(&kernel(&values[start..end]));
+        }
+        start = end + 1;
+    }
+    out
+}
+
+impl Expr {
+    // Fails only when a plugin kernel reports an error
+    fn eval(&self, series: &[Vec<f64>], n: usize) -> Result<Vec<f64>, String> {
+        Ok(match self {
+            Expr::Const(value) => vec![*value; n],
+            Expr::Var(index) => series[*index].clone(),
+            Expr::Neg(inner) => inner.eval(series, n)?.into_iter().map(|v| -v).collect(),
+            Expr::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(series, n)?, rhs.eval(series, n)?);
+                a.iter()
+                    .zip(&b)
+                    .map(|(x, y)| match op {
+                        Op::Add => x + y,
+                        Op::Sub => x - y,
+                        Op::Mul => x * y,
+                        Op::Div => x / y,
+                    })
+                    .collect()
+            }
+            Expr::Func(func, inner) => inner
+                .eval(series, n)?
+                .into_iter()
+                .map(|v| match func {
+                    Func::Abs => v.abs(),
+                    Func::Sqrt => v.sqrt(),
+                    Func::Log => v.ln(),
+                    Func::Exp => v.exp(),
+                })
+                .collect(),
+            Expr::Window(window, inner, w) => {
+                let (values, w) = (inner.eval(series, n)?, *w);
+                match window {
+                    Window::Sma => windowed(&values, w, |v| window_sums(v, w).into_iter().map(|s| s / w as f64).collect()),
+                    Window::Sum => windowed(&values, w, |v| window_sums(v, w)),
+                    Window::Std => windowed(&values, w, |v| window_stds(v, w, 0)),
+                    Window::Min => windowed(&values, w, |v| window_extreme(v, w, |kept, x| kept < x)),
+                    Window::Max => windowed(&values, w, |v| window_extreme(v, w, |kept, x| kept > x)),
+                    // Span convention: alpha = 2 / (span + 1), seeded with the first value
+                    Window::Ema => windowed(&values, 1, |v| crate::ema_slice(v, 2.0 / (w as f64 + 1.0)).unwrap_or_default()),
+                    Window::Lag => (0..n).map(|i| if i >= w { values[i - w] } else { f64::NAN }).collect(),
+                    Window::Diff => (0..n).map(|i| if i >= w { values[i] - values[i - w] } else { f64::NAN }).collect(),
+                }
+            }
+            Expr::Kernel(name, kernel, inner, params) => kernel.run(name, &inner.eval(series, n)?, params)?,
+        })
+    }
+
+    // Leading NaNs the expression produces on finite inputs, not counting any a plugin kernel adds
+    fn warmup(&self) -> usize {
+        match self {
+            Expr::Const(_) | Expr::Var(_) => 0,
+            Expr::Neg(inner) | Expr::Func(_, inner) | Expr::Kernel(_, _, inner, _) => inner.warmup(),
+            Expr::Binary(_, lhs, rhs) => lhs.warmup().max(rhs.warmup()),
+            Expr::Window(Window::Ema, inner, _) => inner.warmup(),
+            Expr::Window(Window::Lag | Window::Diff, inner, w) => inner.warmup() + w,
+            Expr::Window(_, inner, w) => inner.warmup() + w - 1,
+        }
+    }
+}
+
+// A compiled indicator; call it with the series it names, as keywords or a mapping such as a Dataset
+#[pyclass(module = "fina", frozen)]
+pub struct Indicator {
+    source: String,
+    expr: Expr,
+    variables: Vec<String>,
+}
+
+#[pymethods]
+impl Indicator {
+    #[pyo3(signature = (data=None, **columns))]
+    fn __call__(&self, py: Python<'_>, data: Option<&Bound<'_, PyAny>>, columns: Option<&Bound<'_, PyDict>>) -> PyResult<Vec<f64>> {
+        let mut series = Vec::with_capacity(self.variables.len());
+        for name in &self.variables {
+            let value = match columns.map(|c| c.get_item(name)).transpose()?.flatten() {
+                Some(value) => value,
+                None => match data {
+                    Some(data) => data.get_item(name).map_err(|_| {
+                        pyo3::exceptions::PyValueError::new_err(format!("Indicator needs a series named {:?}", name))
+                    })?,
+                    None => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                            "Indicator needs a series named {:?}",
+                            name
+                        )))
+                    }
+                },
+            };
+            let values: Vec<f64> = with_slice!(value.extract::<ArrayLike>()?, values => floats(values).collect());
+            check_finite("Indicator", "series", &values)?;
+            series.push(values);
+        }
+        let n = series[0].len();
+        if n == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("Series cannot be empty"));
+        }
+        if series.iter().any(|s| s.len() != n) {
+            return Err(pyo3::exceptions::PyValueError::new_err("All series must be the same length"));
+        }
+        detach_large(py, n, || self.expr.eval(&series, n)).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    // Series names in order of first appearance
+    #[getter]
+    fn variables(&self) -> Vec<String> {
+        self.variables.clone()
+    }
+
+    // Number of leading NaNs in the output
+    #[getter]
+    fn warmup(&self) -> usize {
+        self.expr.warmup()
+    }
+
+    #[getter]
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Indicator({:?})", self.source)
+    }
+
+    // Pickles as its source text and recompiles on load
+    fn __reduce__<'py>(&self, py: Python<'py>) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyTuple>)> {
+        Ok((py.import("fina")?.getattr("compile_indicator")?, PyTuple::new(py, [&self.source])?))
+    }
+}
+
+// Primitives: sma, ema, std, sum, min, max, lag and diff take (series, window); abs, sqrt, log
+// and exp take a series; + - * / and unary minus combine series and numbers. Kernels from
+// register_kernel are called as name(series, params...)
+#[pyfunction]
+pub fn compile_indicator(source: &str) -> PyResult<Indicator> {
+    let (expr, variables) = parse(source).map_err(SyntaxError::raise)?;
+    if variables.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("compile_indicator: expression does not use any series"));
+    }
+    Ok(Indicator { source: source.to_string(), expr, variables })
+}
+
+// The expression tree and the series names it uses, in order of first appearance
+fn parse(source: &str) -> Result<(Expr, Vec<String>), SyntaxError> {
+    let mut parser = Parser { tokens: tokenize(source)?, pos: 0, variables: Vec::new() };
+    let expr = parser.expr()?;
+    if *parser.peek() != Token::End {
+        return Err(syntax_error(parser.column(), "unexpected trailing input"));
+    }
+    Ok((expr, parser.variables))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, x: &[f64]) -> Vec<f64> {
+        let Ok((expr, _)) = parse(source) else { panic!("{:?} does not parse", source) };
+        expr.eval(&[x.to_vec()], x.len()).unwrap()
+    }
+
+    fn error(source: &str) -> (usize, String) {
+        match parse(source) {
+            Ok(_) => panic!("{:?} parses", source),
+            Err(e) => (e.column, e.message),
+        }
+    }
+
+    fn same(a: &[f64], b: &[f64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x.is_nan() && y.is_nan()) || (x - y).abs() < 1e-12)
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(eval("x + 2 * 3 - -4 / 2", &[1.0]), [9.0]);
+        assert_eq!(eval("(x + 2) * 3", &[1.0]), [9.0]);
+        assert_eq!(eval("x - 4 - 2", &[8.0]), [2.0]);
+        assert_eq!(eval("x / 4 / 2", &[8.0]), [1.0]);
+        assert_eq!(eval("-x * -x", &[3.0]), [9.0]);
+    }
+
+    #[test]
+    fn identifiers_become_series_unless_called() {
+        let Ok((_, variables)) = parse("close - open + close * volume") else { panic!() };
+        assert_eq!(variables, ["close", "open", "volume"]);
+        assert_eq!(error("foo(x, 3)"), (0, "unknown function \"foo\"".to_string()));
+        assert_eq!(error("x + bar(x)").0, 4);
+    }
+
+    #[test]
+    fn arity_errors_point_at_the_offending_token() {
+        assert_eq!(error("sma(x)"), (5, "expected ','".to_string()));
+        assert_eq!(error("sma(x, 2, 3)"), (8, "expected ')'".to_string()));
+        assert_eq!(error("abs(x, 2)"), (5, "expected ')'".to_string()));
+        assert_eq!(error("sma(x, 2.5)"), (7, "sma() needs a positive whole-number window".to_string()));
+        assert_eq!(error("x +"), (3, "unexpected end of expression".to_string()));
+        assert_eq!(error("x x"), (2, "unexpected trailing input".to_string()));
+    }
+
+    #[test]
+    fn windows_recover_after_a_non_finite_value() {
+        let nan = f64::NAN;
+        // 1 / 0 is infinite at index 2; only the windows that contain it are blank
+        let x = [1.0, 2.0, 0.0, 4.0, 5.0, 10.0];
+        assert!(same(&eval("sum(1 / x, 2)", &x), &[nan, 1.5, nan, nan, 0.45, 0.3]));
+        assert!(same(&eval("max(1 / x, 2)", &x), &[nan, 1.0, nan, nan, 0.25, 0.2]));
+        assert!(same(&eval("std(1 / x, 2)", &x), &[nan, 0.25, nan, nan, 0.025, 0.05]));
+        // log of a negative is NaN; ema restarts from the next finite value
+        let y = [1.0, 1.0, -1.0, 1.0, 1.0];
+        assert!(same(&eval("ema(log(y), 3)", &y), &[0.0, 0.0, nan, 0.0, 0.0]));
+        assert!(same(&eval("sma(x, 2) + lag(x, 1)", &x), &[nan, 2.5, 3.0, 2.0, 8.5, 12.5]));
+    }
+}
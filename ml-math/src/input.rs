@@ -0,0 +1,166 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use numpy::PyReadonlyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyList, PyMemoryView, PyTuple};
+
+use crate::dataset::Column;
+use crate::shm::SharedArray;
+
+// Element types the core functions read directly; all arithmetic still accumulates in f64
+pub trait Element: Copy + Send + Sync {
+    fn to_f64(self) -> f64;
+}
+
+impl Element for f64 {
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Element for f32 {
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+pub fn floats<T: Element>(data: &[T]) -> impl Iterator<Item = f64> + '_ {
+    data.iter().map(|x| x.to_f64())
+}
+
+// Inputs at least this long are processed with the GIL released so other Python threads keep running
+const DETACH_THRESHOLD: usize = 100_000;
+
+pub fn detach_large<T: Send>(py: Python<'_>, len: usize, f: impl FnOnce() -> T + Send) -> T {
+    if len >= DETACH_THRESHOLD {
+        py.detach(f)
+    } else {
+        f()
+    }
+}
+
+// 1-D float input: contiguous float64/float32 numpy arrays and Dataset columns are read in place,
+// anything else, SharedArrays included, is copied into a Vec
+pub enum ArrayLike<'py> {
+    Array(PyReadonlyArray1<'py, f64>),
+    Array32(PyReadonlyArray1<'py, f32>),
+    Owned(Vec<f64>),
+    Owned32(Vec<f32>),
+    Shared(Arc<[f64]>),
+    Mapped(Bound<'py, SharedArray>),
+}
+
+pub enum Values<'a> {
+    F64(Cow<'a, [f64]>),
+    F32(Cow<'a, [f32]>),
+}
+
+impl Values<'_> {
+    pub fn len(&self) -> usize {
+        match self {
+            Values::F64(values) => values.len(),
+            Values::F32(values) => values.len(),
+        }
+    }
+}
+
+// Runs the body once per element type with the name bound to a &[f64] or &[f32] slice
+#[macro_export]
+macro_rules! with_slice {
+    ($input:expr, $name:ident => $body:expr) => {
+        match $input.values() {
+            $crate::input::Values::F64(values) => {
+                let $name = &*values;
+                $body
+            }
+            $crate::input::Values::F32(values) => {
+                let $name = &*values;
+                $body
+            }
+        }
+    };
+}
+
+// An ndarray can only exist once numpy has been imported, so checking sys.modules keeps numpy optional
+pub fn numpy_loaded(py: Python<'_>) -> bool {
+    py.import("sys")
+        .and_then(|sys| sys.getattr("modules"))
+        .and_then(|modules| modules.contains("numpy"))
+        .unwrap_or(false)
+}
+
+// array.array('d' or 'f'), memoryviews and other 1-D native float buffers are copied with a single memcpy
+// rather than element by element; PyBuffer is unavailable under the abi3-py37 limited API
+fn extract_buffer<'py>(obj: &Bound<'py, PyAny>) -> Option<ArrayLike<'py>> {
+    let view = PyMemoryView::from(obj).ok()?;
+    let format: String = view.getattr("format").ok()?.extract().ok()?;
+    let ndim: usize = view.getattr("ndim").ok()?.extract().ok()?;
+    let contiguous: bool = view.getattr("c_contiguous").ok()?.extract().ok()?;
+    let double = match format.as_str() {
+        "d" | "@d" | "=d" => true,
+        "f" | "@f" | "=f" => false,
+        _ => return None,
+    };
+    if ndim != 1 || !contiguous {
+        return None;
+    }
+    let bytes = view.call_method0("tobytes").ok()?;
+    let bytes = bytes.cast::<PyBytes>().ok()?.as_bytes();
+    Some(if double {
+        ArrayLike::Owned(bytes.chunks_exact(8).map(|c| f64::from_ne_bytes(c.try_into().unwrap())).collect())
+    } else {
+        ArrayLike::Owned32(bytes.chunks_exact(4).map(|c| f32::from_ne_bytes(c.try_into().unwrap())).collect())
+    })
+}
+
+impl<'py> FromPyObject<'_, 'py> for ArrayLike<'py> {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'_, 'py, PyAny>) -> PyResult<Self> {
+        if let Ok(column) = obj.cast::<Column>() {
+            return Ok(ArrayLike::Shared(column.get().data.clone()));
+        }
+        if let Ok(array) = obj.cast::<SharedArray>() {
+            return Ok(ArrayLike::Mapped(array.to_owned()));
+        }
+        if numpy_loaded(obj.py()) {
+            // Other dtypes fall through to the paths below
+            if let Ok(array) = obj.extract::<PyReadonlyArray1<'py, f64>>() {
+                return Ok(ArrayLike::Array(array));
+            }
+            if let Ok(array) = obj.extract::<PyReadonlyArray1<'py, f32>>() {
+                return Ok(ArrayLike::Array32(array));
+            }
+        }
+        if !obj.is_instance_of::<PyList>() && !obj.is_instance_of::<PyTuple>() {
+            if let Some(values) = extract_buffer(&obj) {
+                return Ok(values);
+            }
+        }
+        Ok(ArrayLike::Owned(obj.extract()?))
+    }
+}
+
+impl ArrayLike<'_> {
+    // Strided views still work, they just pay for a copy
+    pub fn values(&self) -> Values<'_> {
+        match self {
+            ArrayLike::Array(array) => Values::F64(match array.as_slice() {
+                Ok(slice) => Cow::Borrowed(slice),
+                Err(_) => Cow::Owned(array.as_array().iter().copied().collect()),
+            }),
+            ArrayLike::Array32(array) => Values::F32(match array.as_slice() {
+                Ok(slice) => Cow::Borrowed(slice),
+                Err(_) => Cow::Owned(array.as_array().iter().copied().collect()),
+            }),
+            ArrayLike::Owned(values) => Values::F64(Cow::Borrowed(values)),
+            ArrayLike::Owned32(values) => Values::F32(Cow::Borrowed(values)),
+            ArrayLike::Shared(values) => Values::F64(Cow::Borrowed(values)),
+            // Other threads and processes may write the segment meanwhile, so kernels get a snapshot
+            ArrayLike::Mapped(array) => Values::F64(Cow::Owned(array.get().to_vec())),
+        }
+    }
+}
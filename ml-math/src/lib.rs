@@ -1,180 +1,330 @@
 use pyo3::prelude::*;
 
-#[pyfunction]
-fn mean(data: Vec<f64>) -> PyResult<f64> {
+use errors::{same_length, Invalid, Validation};
+use input::{detach_large, floats, ArrayLike, Element};
+use parallel::{map_collect, min_max, sum_map, sum_zip};
+
+mod array;
+mod backtest;
+mod boosting;
+mod credit;
+mod curves;
+mod data;
+mod dataset;
+mod errors;
+mod finance;
+mod fraud;
+mod hashing;
+mod impact;
+mod indicator;
+mod input;
+mod linalg;
+mod margin;
+mod matrix;
+mod optimize;
+mod options;
+mod orderbook;
+mod parallel;
+mod plugins;
+mod progress;
+mod rates;
+mod regime;
+mod regression;
+mod rng;
+mod rolling;
+mod sampling;
+mod shm;
+mod signal;
+mod sketch;
+mod sparse;
+mod state;
+mod stats;
+mod streaming;
+mod synthetic;
+mod tasks;
+mod testing;
+mod timeseries;
+mod vector;
+
+fn slice_mean<T: Element>(data: &[T]) -> Result<f64, Invalid> {
     if data.is_empty() {
-        Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"))
+        Err(Invalid::new("data", "cannot be empty"))
     } else {
-        Ok(data.iter().sum::<f64>() / data.len() as f64)
+        Ok(sum_map(data, |x| x) / data.len() as f64)
     }
 }
 
-#[pyfunction]
-fn variance(data: Vec<f64>) -> PyResult<f64> {
+fn slice_variance<T: Element>(data: &[T]) -> Result<f64, Invalid> {
+    let m = slice_mean(data)?;
+    Ok(sum_map(data, |x| (x - m).powi(2)) / data.len() as f64)
+}
+
+// Geometric and harmonic means need positive data. Strict mode rejects the first value that is not
+// positive; lenient mode short-circuits to NaN if any value is negative or NaN, else to 0.0 if any is zero
+fn non_positive_result<T: Element>(data: &[T], validate: Validation) -> Result<Option<f64>, Invalid> {
     if data.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+        return Err(Invalid::new("data", "cannot be empty"));
     }
-    let m = mean(data.clone())?;
-    Ok(data.iter().map(|x| (x - m).powi(2)).sum::<f64>() / data.len() as f64)
+    let Some(i) = floats(data).position(|x| x <= 0.0 || x.is_nan()) else {
+        return Ok(None);
+    };
+    if validate == Validation::Strict {
+        return Err(Invalid::at("data", i, data[i].to_f64(), "is not positive"));
+    }
+    Ok(Some(if floats(data).any(|x| x.is_nan() || x < 0.0) { f64::NAN } else { 0.0 }))
 }
 
-#[pyfunction]
-fn std_dev(data: Vec<f64>) -> PyResult<f64> {
-    if data.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+fn slice_geometric_mean<T: Element>(data: &[T], validate: Validation) -> Result<f64, Invalid> {
+    if let Some(result) = non_positive_result(data, validate)? {
+        return Ok(result);
     }
-    Ok(variance(data)?.sqrt())
+    // Averaging logs avoids the overflow of multiplying the values directly
+    Ok((sum_map(data, |x| x.ln()) / data.len() as f64).exp())
 }
 
-#[pyfunction]
-fn dot(a: Vec<f64>, b: Vec<f64>) -> PyResult<f64> {
+fn slice_harmonic_mean<T: Element>(data: &[T], validate: Validation) -> Result<f64, Invalid> {
+    if let Some(result) = non_positive_result(data, validate)? {
+        return Ok(result);
+    }
+    Ok(data.len() as f64 / sum_map(data, |x| 1.0 / x))
+}
+
+fn slice_dot<A: Element, B: Element>(a: &[A], b: &[B]) -> Result<f64, Invalid> {
     if a.len() != b.len() {
-        Err(pyo3::exceptions::PyValueError::new_err("Vectors must be same length"))
+        Err(Invalid::new("b", format!("has {} elements but a has {}", b.len(), a.len())))
     } else {
-        Ok(a.iter().zip(b.iter()).map(|(x, y)| x * y).sum())
+        Ok(sum_zip(a, b, |x, y| x * y))
     }
 }
 
 #[pyfunction]
-fn euclidean(a: Vec<f64>, b: Vec<f64>) -> PyResult<f64> {
+fn mean(py: Python<'_>, data: ArrayLike) -> PyResult<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || slice_mean(data))).map_err(|e| e.raise("mean"))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, validate=Validation::Strict))]
+fn geometric_mean(py: Python<'_>, data: ArrayLike, validate: Validation) -> PyResult<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || slice_geometric_mean(data, validate)))
+        .map_err(|e| e.raise("geometric_mean"))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, validate=Validation::Strict))]
+fn harmonic_mean(py: Python<'_>, data: ArrayLike, validate: Validation) -> PyResult<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || slice_harmonic_mean(data, validate)))
+        .map_err(|e| e.raise("harmonic_mean"))
+}
+
+#[pyfunction]
+fn variance(py: Python<'_>, data: ArrayLike) -> PyResult<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || slice_variance(data))).map_err(|e| e.raise("variance"))
+}
+
+#[pyfunction]
+fn std_dev(py: Python<'_>, data: ArrayLike) -> PyResult<f64> {
+    let variance = with_slice!(data, data => detach_large(py, data.len(), || slice_variance(data)));
+    Ok(variance.map_err(|e| e.raise("std_dev"))?.sqrt())
+}
+
+#[pyfunction]
+fn dot(py: Python<'_>, a: ArrayLike, b: ArrayLike) -> PyResult<f64> {
+    with_slice!(a, a => with_slice!(b, b => {
+        detach_large(py, a.len(), || slice_dot(a, b))
+    }))
+    .map_err(|e| e.raise("dot"))
+}
+
+#[pyfunction]
+fn euclidean(py: Python<'_>, a: ArrayLike, b: ArrayLike) -> PyResult<f64> {
+    with_slice!(a, a => with_slice!(b, b => {
+        detach_large(py, a.len(), || euclidean_slice(a, b))
+    }))
+    .map_err(|e| e.raise("euclidean"))
+}
+
+fn euclidean_slice<A: Element, B: Element>(a: &[A], b: &[B]) -> Result<f64, Invalid> {
     if a.len() != b.len() {
-        Err(pyo3::exceptions::PyValueError::new_err("Vectors must be same length"))
+        Err(Invalid::new("b", format!("has {} elements but a has {}", b.len(), a.len())))
     } else {
-        Ok(a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt())
+        Ok(sum_zip(a, b, |x, y| (x - y).powi(2)).sqrt())
     }
 }
 
-#[pyfunction]
-fn sigmoid(x: f64) -> PyResult<f64> {
+fn sigmoid_value(x: f64) -> f64 {
     // Handle extreme values to prevent overflow
     if x > 500.0 {
-        Ok(1.0)
+        1.0
     } else if x < -500.0 {
-        Ok(0.0)
+        0.0
     } else {
-        Ok(1.0 / (1.0 + (-x).exp()))
+        1.0 / (1.0 + (-x).exp())
     }
 }
 
+#[pyfunction]
+fn sigmoid(x: f64) -> PyResult<f64> {
+    Ok(sigmoid_value(x))
+}
+
 #[pyfunction]
 fn relu(x: f64) -> PyResult<f64> {
     Ok(x.max(0.0))
 }
 
 #[pyfunction]
-fn softmax(data: Vec<f64>) -> PyResult<Vec<f64>> {
+fn softmax(py: Python<'_>, data: ArrayLike) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || softmax_slice(data))).map_err(|e| e.raise("softmax"))
+}
+
+fn softmax_slice<T: Element>(data: &[T]) -> Result<Vec<f64>, Invalid> {
     if data.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+        return Err(Invalid::new("data", "cannot be empty"));
     }
     
     // Subtract max for numerical stability
-    let max_val = data.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-    let exp_values: Vec<f64> = data.iter().map(|&x| (x - max_val).exp()).collect();
-    let sum_exp = exp_values.iter().sum::<f64>();
+    let (_, max_val) = min_max(data);
+    let exp_values = map_collect(data, |x| (x - max_val).exp());
+    let sum_exp = sum_map(&exp_values, |x| x);
     
     if sum_exp == 0.0 {
-        return Err(pyo3::exceptions::PyValueError::new_err("Underflow in softmax computation"));
+        return Err(Invalid::new("data", "underflows: every exp(x - max) is zero"));
     }
     
-    Ok(exp_values.iter().map(|&x| x / sum_exp).collect())
+    Ok(map_collect(&exp_values, |x| x / sum_exp))
 }
 
 #[pyfunction]
-fn cross_entropy(pred: Vec<f64>, target: Vec<f64>) -> PyResult<f64> {
-    if pred.len() != target.len() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Vectors must be same length"));
-    }
-    if pred.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Vectors cannot be empty"));
-    }
+#[pyo3(signature = (pred, target, validate=Validation::Strict))]
+fn cross_entropy(py: Python<'_>, pred: ArrayLike, target: ArrayLike, validate: Validation) -> PyResult<f64> {
+    with_slice!(pred, pred => with_slice!(target, target => {
+        detach_large(py, pred.len(), || cross_entropy_slice(pred, target, validate))
+    }))
+    .map_err(|e| e.raise("cross_entropy"))
+}
+
+fn cross_entropy_slice<P: Element, T: Element>(pred: &[P], target: &[T], validate: Validation) -> Result<f64, Invalid> {
+    same_length("pred", pred.len(), "target", target.len())?;
     
     let mut loss = 0.0;
-    for (p, t) in pred.iter().zip(target.iter()) {
-        if *p <= 0.0 {
-            return Err(pyo3::exceptions::PyValueError::new_err("Predictions must be positive for cross entropy"));
-        }
+    for (i, (p, t)) in floats(pred).zip(floats(target)).enumerate() {
+        // Lenient mode clamps like log_loss; max also maps NaN to the floor
+        let p = match validate {
+            Validation::Lenient => p.max(f64::EPSILON),
+            Validation::Strict if p <= 0.0 || p.is_nan() => return Err(Invalid::at("pred", i, p, "is not positive")),
+            Validation::Strict => p,
+        };
         loss += t * p.ln();
     }
     Ok(-loss)
 }
 
 #[pyfunction]
-fn mse(pred: Vec<f64>, target: Vec<f64>) -> PyResult<f64> {
-    if pred.len() != target.len() {
-        Err(pyo3::exceptions::PyValueError::new_err("Vectors must be same length"))
-    } else if pred.is_empty() {
-        Err(pyo3::exceptions::PyValueError::new_err("Vectors cannot be empty"))
-    } else {
-        Ok(pred.iter().zip(target.iter()).map(|(p, t)| (p - t).powi(2)).sum::<f64>() / pred.len() as f64)
-    }
+fn mse(py: Python<'_>, pred: ArrayLike, target: ArrayLike) -> PyResult<f64> {
+    with_slice!(pred, pred => with_slice!(target, target => {
+        detach_large(py, pred.len(), || mse_slice(pred, target))
+    }))
+    .map_err(|e| e.raise("mse"))
+}
+
+fn mse_slice<P: Element, T: Element>(pred: &[P], target: &[T]) -> Result<f64, Invalid> {
+    same_length("pred", pred.len(), "target", target.len())?;
+    Ok(floats(pred).zip(floats(target)).map(|(p, t)| (p - t).powi(2)).sum::<f64>() / pred.len() as f64)
 }
 
 #[pyfunction]
-fn min_max_normalize(data: Vec<f64>) -> PyResult<Vec<f64>> {
+#[pyo3(signature = (data, validate=Validation::Strict))]
+fn min_max_normalize(py: Python<'_>, data: ArrayLike, validate: Validation) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || min_max_slice(data, validate))).map_err(|e| e.raise("min_max_normalize"))
+}
+
+fn min_max_slice<T: Element>(data: &[T], validate: Validation) -> Result<Vec<f64>, Invalid> {
     if data.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+        return Err(Invalid::new("data", "cannot be empty"));
     }
     
-    let min_val = data.iter().cloned().fold(f64::INFINITY, f64::min);
-    let max_val = data.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let (min_val, max_val) = min_max(data);
     
     if (max_val - min_val).abs() < f64::EPSILON {
-        Err(pyo3::exceptions::PyValueError::new_err("All elements are equal, cannot normalize"))
+        if validate == Validation::Lenient {
+            return Ok(vec![0.0; data.len()]);
+        }
+        Err(Invalid::new("data", format!("is constant ({}), cannot normalize", min_val)))
     } else {
-        Ok(data.iter().map(|&x| (x - min_val) / (max_val - min_val)).collect())
+        Ok(map_collect(data, |x| (x - min_val) / (max_val - min_val)))
     }
 }
 
 #[pyfunction]
-fn z_score_normalize(data: Vec<f64>) -> PyResult<Vec<f64>> {
+#[pyo3(signature = (data, validate=Validation::Strict))]
+fn z_score_normalize(py: Python<'_>, data: ArrayLike, validate: Validation) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || z_score_slice(data, validate))).map_err(|e| e.raise("z_score_normalize"))
+}
+
+fn z_score_slice<T: Element>(data: &[T], validate: Validation) -> Result<Vec<f64>, Invalid> {
     if data.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+        return Err(Invalid::new("data", "cannot be empty"));
     }
     
-    let m = mean(data.clone())?;
-    let s = std_dev(data.clone())?;
+    let m = slice_mean(data)?;
+    let s = slice_variance(data)?.sqrt();
     
     if s.abs() < f64::EPSILON {
-        return Err(pyo3::exceptions::PyValueError::new_err("Standard deviation is zero, cannot normalize"));
+        if validate == Validation::Lenient {
+            return Ok(vec![0.0; data.len()]);
+        }
+        return Err(Invalid::new("data", "has zero standard deviation, cannot normalize"));
     }
     
-    Ok(data.iter().map(|&x| (x - m) / s).collect())
+    Ok(map_collect(data, |x| (x - m) / s))
 }
 
 #[pyfunction]
-fn cosine_similarity(a: Vec<f64>, b: Vec<f64>) -> PyResult<f64> {
-    if a.len() != b.len() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Vectors must be same length"));
-    }
-    if a.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Vectors cannot be empty"));
-    }
+#[pyo3(signature = (a, b, validate=Validation::Strict))]
+fn cosine_similarity(py: Python<'_>, a: ArrayLike, b: ArrayLike, validate: Validation) -> PyResult<f64> {
+    with_slice!(a, a => with_slice!(b, b => {
+        detach_large(py, a.len(), || cosine_slice(a, b, validate))
+    }))
+    .map_err(|e| e.raise("cosine_similarity"))
+}
+
+fn cosine_slice<A: Element, B: Element>(a: &[A], b: &[B], validate: Validation) -> Result<f64, Invalid> {
+    same_length("a", a.len(), "b", b.len())?;
     
-    let dot_ab = dot(a.clone(), b.clone())?;
-    let norm_a_squared = dot(a.clone(), a.clone())?;
-    let norm_b_squared = dot(b.clone(), b.clone())?;
+    let dot_ab = slice_dot(a, b)?;
+    let norm_a_squared = slice_dot(a, a)?;
+    let norm_b_squared = slice_dot(b, b)?;
     let norm_a = norm_a_squared.sqrt();
     let norm_b = norm_b_squared.sqrt();
     
-    if norm_a.abs() < f64::EPSILON || norm_b.abs() < f64::EPSILON {
-        return Err(pyo3::exceptions::PyValueError::new_err("Cannot compute cosine similarity for zero vectors"));
+    if validate == Validation::Lenient && (norm_a.abs() < f64::EPSILON || norm_b.abs() < f64::EPSILON) {
+        return Ok(0.0);
+    }
+    if norm_a.abs() < f64::EPSILON {
+        return Err(Invalid::new("a", "is a zero vector"));
+    }
+    if norm_b.abs() < f64::EPSILON {
+        return Err(Invalid::new("b", "is a zero vector"));
     }
     
     Ok(dot_ab / (norm_a * norm_b))
 }
 
 #[pyfunction]
-fn log_loss(pred: Vec<f64>, target: Vec<f64>) -> PyResult<f64> {
-    if pred.len() != target.len() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Vectors must be same length"));
-    }
-    if pred.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Vectors cannot be empty"));
-    }
+fn log_loss(py: Python<'_>, pred: ArrayLike, target: ArrayLike) -> PyResult<f64> {
+    with_slice!(pred, pred => with_slice!(target, target => {
+        detach_large(py, pred.len(), || log_loss_slice(pred, target))
+    }))
+    .map_err(|e| e.raise("log_loss"))
+}
+
+fn log_loss_slice<P: Element, T: Element>(pred: &[P], target: &[T]) -> Result<f64, Invalid> {
+    same_length("pred", pred.len(), "target", target.len())?;
     
     let mut loss = 0.0;
-    for (p, t) in pred.iter().zip(target.iter()) {
-        // Clamp predictions to avoid log(0)
+    for (p, t) in floats(pred).zip(floats(target)) {
+        // Clamp predictions to avoid log(0); max also maps NaN to the floor, which clamp would propagate
+        #[allow(clippy::manual_clamp)]
         let p_clamped = p.max(f64::EPSILON).min(1.0 - f64::EPSILON);
         loss += t * p_clamped.ln() + (1.0 - t) * (1.0 - p_clamped).ln();
     }
@@ -182,19 +332,23 @@ fn log_loss(pred: Vec<f64>, target: Vec<f64>) -> PyResult<f64> {
 }
 
 #[pyfunction]
-fn ema(data: Vec<f64>, alpha: f64) -> PyResult<Vec<f64>> {
+fn ema(py: Python<'_>, data: ArrayLike, alpha: f64) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || ema_slice(data, alpha))).map_err(|e| e.raise("ema"))
+}
+
+fn ema_slice<T: Element>(data: &[T], alpha: f64) -> Result<Vec<f64>, Invalid> {
     if data.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+        return Err(Invalid::new("data", "cannot be empty"));
     }
     if !(0.0..=1.0).contains(&alpha) {
-        return Err(pyo3::exceptions::PyValueError::new_err("Alpha must be between 0 and 1"));
+        return Err(Invalid::new("alpha", format!("= {} must be between 0 and 1", alpha)));
     }
     
     let mut result = Vec::with_capacity(data.len());
-    let mut ema_value = data[0];
+    let mut ema_value = data[0].to_f64();
     result.push(ema_value);
     
-    for &x in &data[1..] {
+    for x in floats(&data[1..]) {
         ema_value = alpha * x + (1.0 - alpha) * ema_value;
         result.push(ema_value);
     }
@@ -211,24 +365,52 @@ fn leaky_relu(x: f64, alpha: f64) -> PyResult<f64> {
     Ok(if x >= 0.0 { x } else { alpha * x })
 }
 
+// Elementwise activations over whole arrays
 #[pyfunction]
-fn rms(data: Vec<f64>) -> PyResult<f64> {
+fn sigmoid_vec(py: Python<'_>, data: ArrayLike) -> Vec<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || map_collect(data, sigmoid_value)))
+}
+
+#[pyfunction]
+fn relu_vec(py: Python<'_>, data: ArrayLike) -> Vec<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || map_collect(data, |x| x.max(0.0))))
+}
+
+#[pyfunction]
+fn tanh_vec(py: Python<'_>, data: ArrayLike) -> Vec<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || map_collect(data, f64::tanh)))
+}
+
+#[pyfunction]
+fn leaky_relu_vec(py: Python<'_>, data: ArrayLike, alpha: f64) -> Vec<f64> {
+    with_slice!(data, data => {
+        detach_large(py, data.len(), || map_collect(data, |x| if x >= 0.0 { x } else { alpha * x }))
+    })
+}
+
+#[pyfunction]
+fn rms(py: Python<'_>, data: ArrayLike) -> PyResult<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || rms_slice(data))).map_err(|e| e.raise("rms"))
+}
+
+fn rms_slice<T: Element>(data: &[T]) -> Result<f64, Invalid> {
     if data.is_empty() {
-        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+        return Err(Invalid::new("data", "cannot be empty"));
     }
-    Ok((data.iter().map(|x| x.powi(2)).sum::<f64>() / data.len() as f64).sqrt())
+    Ok((floats(data).map(|x| x.powi(2)).sum::<f64>() / data.len() as f64).sqrt())
 }
 
 #[pyfunction]
 fn clamp(x: f64, min_val: f64, max_val: f64) -> PyResult<f64> {
     if min_val > max_val {
-        return Err(pyo3::exceptions::PyValueError::new_err("min_val cannot be greater than max_val"));
+        return Err(Invalid::new("min_val", format!("= {} is greater than max_val = {}", min_val, max_val)).raise("clamp"));
     }
     Ok(x.clamp(min_val, max_val))
 }
 
 #[pymodule]
 fn fina(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("InputError", m.py().get_type::<errors::InputError>())?;
     m.add_function(wrap_pyfunction!(mean, m)?)?;
     m.add_function(wrap_pyfunction!(variance, m)?)?;
     m.add_function(wrap_pyfunction!(std_dev, m)?)?;
@@ -248,5 +430,177 @@ fn fina(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(leaky_relu, m)?)?;
     m.add_function(wrap_pyfunction!(rms, m)?)?;
     m.add_function(wrap_pyfunction!(clamp, m)?)?;
+    m.add_function(wrap_pyfunction!(sigmoid_vec, m)?)?;
+    m.add_function(wrap_pyfunction!(relu_vec, m)?)?;
+    m.add_function(wrap_pyfunction!(tanh_vec, m)?)?;
+    m.add_function(wrap_pyfunction!(leaky_relu_vec, m)?)?;
+    m.add_class::<streaming::StreamingAnomalyDetector>()?;
+    m.add_function(wrap_pyfunction!(regime::change_points, m)?)?;
+    m.add_class::<regime::GaussianHmm>()?;
+    m.add_function(wrap_pyfunction!(regime::hmm_fit, m)?)?;
+    m.add_function(wrap_pyfunction!(regime::hmm_viterbi, m)?)?;
+    m.add_function(wrap_pyfunction!(regime::transition_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(regime::simulate_markov, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::sample_entropy, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::approximate_entropy, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::shannon_entropy, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::run_lengths, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::longest_streak, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::segment_series, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::matrix_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::detrend, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::gradient, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::trapz, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::cumtrapz, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::dwt, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::idwt, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::hilbert, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::decimate, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::lttb_downsample, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::lowpass, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::highpass, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::bandpass, m)?)?;
+    m.add_function(wrap_pyfunction!(optimize::brentq, m)?)?;
+    m.add_function(wrap_pyfunction!(optimize::newton, m)?)?;
+    m.add_function(wrap_pyfunction!(optimize::golden_section_minimize, m)?)?;
+    m.add_class::<optimize::OptimizeResult>()?;
+    m.add_function(wrap_pyfunction!(optimize::minimize, m)?)?;
+    m.add_class::<curves::NelsonSiegel>()?;
+    m.add_function(wrap_pyfunction!(curves::nelson_siegel_fit, m)?)?;
+    m.add_class::<curves::SviParams>()?;
+    m.add_function(wrap_pyfunction!(curves::svi_fit, m)?)?;
+    m.add_class::<curves::CubicSpline>()?;
+    m.add_function(wrap_pyfunction!(curves::cubic_spline_fit, m)?)?;
+    m.add_function(wrap_pyfunction!(curves::cubic_spline_eval, m)?)?;
+    m.add_function(wrap_pyfunction!(curves::bilinear_interp, m)?)?;
+    m.add_class::<curves::VolSurface>()?;
+    m.add_function(wrap_pyfunction!(options::lsm_american_price, m)?)?;
+    m.add_function(wrap_pyfunction!(options::mc_price, m)?)?;
+    m.add_class::<options::McParams>()?;
+    m.add_class::<options::McGreeks>()?;
+    m.add_function(wrap_pyfunction!(options::mc_greeks, m)?)?;
+    m.add_function(wrap_pyfunction!(options::pnl_ladder, m)?)?;
+    m.add_function(wrap_pyfunction!(rates::fra_rate, m)?)?;
+    m.add_function(wrap_pyfunction!(rates::swap_rate, m)?)?;
+    m.add_function(wrap_pyfunction!(rates::dv01, m)?)?;
+    m.add_class::<rates::DiscountCurve>()?;
+    m.add_function(wrap_pyfunction!(margin::initial_margin_portfolio, m)?)?;
+    m.add_function(wrap_pyfunction!(margin::leverage_ratio, m)?)?;
+    m.add_class::<orderbook::OrderBookSim>()?;
+    m.add_function(wrap_pyfunction!(orderbook::queue_position, m)?)?;
+    m.add_function(wrap_pyfunction!(orderbook::fill_probability, m)?)?;
+    m.add_function(wrap_pyfunction!(impact::sqrt_impact, m)?)?;
+    m.add_function(wrap_pyfunction!(impact::almgren_chriss_schedule, m)?)?;
+    m.add_function(wrap_pyfunction!(synthetic::generate_synthetic_ohlcv, m)?)?;
+    m.add_function(wrap_pyfunction!(backtest::stationary_bootstrap, m)?)?;
+    m.add_function(wrap_pyfunction!(backtest::probabilistic_sharpe, m)?)?;
+    m.add_function(wrap_pyfunction!(backtest::deflated_sharpe, m)?)?;
+    m.add_function(wrap_pyfunction!(backtest::cpcv_splits, m)?)?;
+    m.add_function(wrap_pyfunction!(backtest::purged_kfold_indices, m)?)?;
+    m.add_class::<streaming::RecursiveLeastSquares>()?;
+    m.add_function(wrap_pyfunction!(parallel::set_parallel_threshold, m)?)?;
+    m.add_function(wrap_pyfunction!(parallel::get_parallel_threshold, m)?)?;
+    m.add_function(wrap_pyfunction!(regression::quantile_regression, m)?)?;
+    m.add_function(wrap_pyfunction!(regression::ridge, m)?)?;
+    m.add_function(wrap_pyfunction!(regression::lasso, m)?)?;
+    m.add_function(wrap_pyfunction!(regression::elastic_net, m)?)?;
+    m.add_function(wrap_pyfunction!(regression::huber_regression, m)?)?;
+    m.add_function(wrap_pyfunction!(regression::theil_sen, m)?)?;
+    m.add_function(wrap_pyfunction!(regression::isotonic_fit, m)?)?;
+    m.add_function(wrap_pyfunction!(regression::platt_scaling, m)?)?;
+    m.add_class::<vector::Vector>()?;
+    m.add_class::<matrix::Matrix>()?;
+    m.add_class::<boosting::GbmModel>()?;
+    m.add_function(wrap_pyfunction!(boosting::gbm_fit, m)?)?;
+    m.add_function(wrap_pyfunction!(boosting::gbm_predict, m)?)?;
+    m.add_function(wrap_pyfunction!(rng::seed, m)?)?;
+    m.add_function(wrap_pyfunction!(linalg::svd, m)?)?;
+    m.add_function(wrap_pyfunction!(linalg::cholesky, m)?)?;
+    m.add_function(wrap_pyfunction!(linalg::qr, m)?)?;
+    m.add_function(wrap_pyfunction!(linalg::lu, m)?)?;
+    m.add_function(wrap_pyfunction!(linalg::solve, m)?)?;
+    m.add_function(wrap_pyfunction!(linalg::lstsq, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::grouped_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(sparse::sparse_dot, m)?)?;
+    m.add_function(wrap_pyfunction!(sparse::sparse_cosine, m)?)?;
+    m.add_class::<sparse::SparseVector>()?;
+    m.add_function(wrap_pyfunction!(hashing::minhash_signature, m)?)?;
+    m.add_class::<hashing::LSHIndex>()?;
+    m.add_class::<stats::OnlineStats>()?;
+    m.add_function(wrap_pyfunction!(sampling::reservoir_sample, m)?)?;
+    m.add_function(wrap_pyfunction!(sampling::stratified_sample_indices, m)?)?;
+    m.add_function(wrap_pyfunction!(rolling::rolling_mean, m)?)?;
+    m.add_function(wrap_pyfunction!(rolling::rolling_std, m)?)?;
+    m.add_function(wrap_pyfunction!(rolling::rolling_min, m)?)?;
+    m.add_function(wrap_pyfunction!(rolling::rolling_max, m)?)?;
+    m.add_function(wrap_pyfunction!(rolling::rolling_sum, m)?)?;
+    m.add_class::<sketch::HyperLogLog>()?;
+    m.add_class::<sketch::CountMinSketch>()?;
+    m.add_function(wrap_pyfunction!(rolling::rolling_apply, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::ewm_var, m)?)?;
+    m.add_function(wrap_pyfunction!(timeseries::ewm_std, m)?)?;
+    m.add_class::<sketch::TDigest>()?;
+    m.add_function(wrap_pyfunction!(stats::weighted_quantile, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::weighted_median, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::circular_mean, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::circular_std, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::circular_corr, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::mode, m)?)?;
+    testing::register(m)?;
+    m.add_function(wrap_pyfunction!(stats::skewness, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::kurtosis, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::covariance, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::cov_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::pearson, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::corr_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(tasks::submit, m)?)?;
+    m.add_function(wrap_pyfunction!(tasks::submit_async, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::spearman, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::kendall_tau, m)?)?;
+    m.add_function(wrap_pyfunction!(indicator::compile_indicator, m)?)?;
+    m.add_class::<indicator::Indicator>()?;
+    m.add_function(wrap_pyfunction!(plugins::register_kernel, m)?)?;
+    m.add_function(wrap_pyfunction!(plugins::list_kernels, m)?)?;
+    m.add_function(wrap_pyfunction!(plugins::call_kernel, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::weighted_mean, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::weighted_variance, m)?)?;
+    m.add_function(wrap_pyfunction!(stats::weighted_std, m)?)?;
+    m.add_function(wrap_pyfunction!(geometric_mean, m)?)?;
+    m.add_function(wrap_pyfunction!(harmonic_mean, m)?)?;
+    dataset::register(m)?;
+    m.add_class::<shm::SharedArray>()?;
+    m.add_class::<data::ValidationReport>()?;
+    m.add_function(wrap_pyfunction!(data::validate_series, m)?)?;
+    m.add_function(wrap_pyfunction!(data::dedupe_timestamps, m)?)?;
+    m.add_function(wrap_pyfunction!(data::adjust_for_splits, m)?)?;
+    m.add_function(wrap_pyfunction!(data::adjust_for_dividends, m)?)?;
+    m.add_function(wrap_pyfunction!(finance::convert_series, m)?)?;
+    m.add_function(wrap_pyfunction!(finance::cross_rate, m)?)?;
+    m.add_function(wrap_pyfunction!(finance::hedged_returns, m)?)?;
+    m.add_function(wrap_pyfunction!(finance::real_returns, m)?)?;
+    m.add_function(wrap_pyfunction!(finance::deflate_series, m)?)?;
+    m.add_function(wrap_pyfunction!(finance::realized_gains, m)?)?;
+    m.add_class::<finance::ProjectionResult>()?;
+    m.add_function(wrap_pyfunction!(finance::monte_carlo_projection, m)?)?;
+    m.add_function(wrap_pyfunction!(credit::ks_statistic, m)?)?;
+    m.add_function(wrap_pyfunction!(credit::gini_from_auc, m)?)?;
+    m.add_function(wrap_pyfunction!(credit::population_stability_index, m)?)?;
+    m.add_function(wrap_pyfunction!(credit::expected_loss, m)?)?;
+    m.add_function(wrap_pyfunction!(credit::vintage_default_curve, m)?)?;
+    m.add_function(wrap_pyfunction!(credit::roll_rate_matrix, m)?)?;
+    m.add_class::<fraud::BenfordResult>()?;
+    m.add_function(wrap_pyfunction!(fraud::benford_test, m)?)?;
+    m.add_function(wrap_pyfunction!(hashing::feature_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(hashing::ngram_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(array::argsort, m)?)?;
+    m.add_function(wrap_pyfunction!(array::top_k, m)?)?;
+    m.add_function(wrap_pyfunction!(array::argmax, m)?)?;
+    m.add_function(wrap_pyfunction!(array::argmin, m)?)?;
+    m.add_function(wrap_pyfunction!(array::searchsorted, m)?)?;
+    m.add_function(wrap_pyfunction!(array::value_counts, m)?)?;
+    m.add_function(wrap_pyfunction!(array::unique, m)?)?;
+    m.add_function(wrap_pyfunction!(array::unique_counts, m)?)?;
+    m.add_function(wrap_pyfunction!(array::intersect_sorted, m)?)?;
+    m.add_function(wrap_pyfunction!(array::union_sorted, m)?)?;
     Ok(())
-}
\ No newline at end of file
+}
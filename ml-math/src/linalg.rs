@@ -0,0 +1,540 @@
+// Dense linear algebra: helpers shared by the fitting routines plus the Matrix decompositions
+
+use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+
+use crate::matrix::Matrix;
+use crate::vector::Vector;
+
+// Solve a small square system with Gaussian elimination and partial pivoting.
+// Returns None when the matrix is numerically singular.
+pub fn solve_dense(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in (col + 1)..n {
+            let factor = a[row][col] / a[col][col];
+            if factor == 0.0 {
+                continue;
+            }
+            let (upper, lower) = a.split_at_mut(row);
+            for (x, &p) in lower[0][col..].iter_mut().zip(&upper[col][col..]) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let s: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - s) / a[row][row];
+    }
+    Some(x)
+}
+
+// Row-major LU factorization PA = LU with partial pivoting; L has a unit diagonal and
+// both factors share one buffer
+pub struct Lu {
+    pub n: usize,
+    pub lu: Vec<f64>,
+    pub perm: Vec<usize>,
+    pub sign: f64,
+    pub singular: bool,
+}
+
+pub fn lu_factor(a: &Matrix) -> Lu {
+    let n = a.rows;
+    let mut lu = a.data.clone();
+    let mut perm: Vec<usize> = (0..n).collect();
+    let mut sign = 1.0;
+    // Pivots this small relative to the largest entry are treated as exact zeros
+    let scale = lu.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    let tiny = scale * n as f64 * f64::EPSILON;
+    let mut singular = scale == 0.0 && n > 0;
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| lu[i * n + col].abs().total_cmp(&lu[j * n + col].abs())).unwrap();
+        if lu[pivot * n + col].abs() <= tiny {
+            singular = true;
+            continue;
+        }
+        if pivot != col {
+            for k in 0..n {
+                lu.swap(col * n + k, pivot * n + k);
+            }
+            perm.swap(col, pivot);
+            sign = -sign;
+        }
+        let (upper, lower) = lu.split_at_mut((col + 1) * n);
+        let pivot_row = &upper[col * n..];
+        for row in lower.chunks_mut(n) {
+            let factor = row[col] / pivot_row[col];
+            row[col] = factor;
+            if factor != 0.0 {
+                for (x, &p) in row[col + 1..].iter_mut().zip(&pivot_row[col + 1..]) {
+                    *x -= factor * p;
+                }
+            }
+        }
+    }
+    Lu { n, lu, perm, sign, singular }
+}
+
+impl Lu {
+    pub fn det(&self) -> f64 {
+        if self.singular {
+            return 0.0;
+        }
+        (0..self.n).map(|i| self.lu[i * self.n + i]).product::<f64>() * self.sign
+    }
+
+    // Forward then back substitution; only meaningful when the factorization is not singular
+    pub fn solve(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.n;
+        let mut x: Vec<f64> = self.perm.iter().map(|&p| b[p]).collect();
+        for i in 0..n {
+            let s: f64 = self.lu[i * n..i * n + i].iter().zip(&x[..i]).map(|(l, v)| l * v).sum();
+            x[i] -= s;
+        }
+        for i in (0..n).rev() {
+            let s: f64 = self.lu[i * n + i + 1..(i + 1) * n].iter().zip(&x[i + 1..]).map(|(u, v)| u * v).sum();
+            x[i] = (x[i] - s) / self.lu[i * n + i];
+        }
+        x
+    }
+
+    // Solves A^T x = b: with PA = LU, A^T = U^T L^T P
+    pub fn solve_transpose(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.n;
+        let mut w = b.to_vec();
+        for i in 0..n {
+            let s: f64 = (0..i).map(|k| self.lu[k * n + i] * w[k]).sum();
+            w[i] = (w[i] - s) / self.lu[i * n + i];
+        }
+        for i in (0..n).rev() {
+            let s: f64 = (i + 1..n).map(|k| self.lu[k * n + i] * w[k]).sum();
+            w[i] -= s;
+        }
+        let mut x = vec![0.0; n];
+        for (&p, v) in self.perm.iter().zip(w) {
+            x[p] = v;
+        }
+        x
+    }
+
+    // Hager's estimate of ||A^-1||_1 times ||A||_1; a lower bound that is usually within a
+    // small factor of the true 1-norm condition number, at a few solves instead of an inverse
+    pub fn condition_estimate(&self, a: &Matrix) -> f64 {
+        let n = self.n;
+        if self.singular {
+            return f64::INFINITY;
+        }
+        if n == 0 {
+            return 0.0;
+        }
+        let norm_a = (0..n).map(|j| (0..n).map(|i| a.get(i, j).abs()).sum::<f64>()).fold(0.0, f64::max);
+        let mut x = vec![1.0 / n as f64; n];
+        let mut estimate = 0.0;
+        for _ in 0..5 {
+            let y = self.solve(&x);
+            estimate = y.iter().map(|v| v.abs()).sum::<f64>();
+            let signs: Vec<f64> = y.iter().map(|&v| if v >= 0.0 { 1.0 } else { -1.0 }).collect();
+            let z = self.solve_transpose(&signs);
+            let (j, z_max) = z.iter().enumerate().fold((0, 0.0), |best, (i, v)| if v.abs() > best.1 { (i, v.abs()) } else { best });
+            let zx: f64 = z.iter().zip(&x).map(|(a, b)| a * b).sum();
+            if z_max <= zx {
+                break;
+            }
+            x = vec![0.0; n];
+            x[j] = 1.0;
+        }
+        norm_a * estimate
+    }
+}
+
+// Right-hand side of a solve: a Vector or any sequence of floats
+#[derive(FromPyObject)]
+pub enum Rhs<'py> {
+    Vector(PyRef<'py, Vector>),
+    Values(Vec<f64>),
+}
+
+impl Rhs<'_> {
+    pub fn values(&self) -> &[f64] {
+        match self {
+            Rhs::Vector(v) => &v.data,
+            Rhs::Values(v) => v,
+        }
+    }
+}
+
+// Factors A = P @ L @ U with P a permutation, L unit lower triangular and U upper triangular.
+// Singular input still factors; U then has a (numerically) zero diagonal entry
+#[pyfunction]
+pub fn lu(matrix: &Bound<'_, PyAny>) -> PyResult<(Matrix, Matrix, Matrix)> {
+    let a = Matrix::new(matrix)?;
+    a.require_square()?;
+    let n = a.rows;
+    let f = lu_factor(&a);
+    let mut p = Matrix::zeros(n, n);
+    let mut l = Matrix::identity(n);
+    let mut u = Matrix::zeros(n, n);
+    for (i, &src) in f.perm.iter().enumerate() {
+        p.data[src * n + i] = 1.0;
+    }
+    for i in 0..n {
+        for j in 0..n {
+            let v = f.lu[i * n + j];
+            if j < i {
+                l.data[i * n + j] = v;
+            } else {
+                u.data[i * n + j] = v;
+            }
+        }
+    }
+    Ok((p, l, u))
+}
+
+// Solves A x = b by pivoted LU; with return_cond=True returns (x, cond) where cond estimates
+// the 1-norm condition number of A
+#[pyfunction]
+#[pyo3(signature = (matrix, b, return_cond=false))]
+pub fn solve(py: Python<'_>, matrix: &Bound<'_, PyAny>, b: Rhs<'_>, return_cond: bool) -> PyResult<Py<PyAny>> {
+    let a = Matrix::new(matrix)?;
+    a.require_square()?;
+    let b = b.values();
+    if b.len() != a.rows {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "b has {} elements but the matrix has {} rows",
+            b.len(),
+            a.rows
+        )));
+    }
+    if a.data.iter().chain(b).any(|v| !v.is_finite()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Inputs must be finite"));
+    }
+    let f = lu_factor(&a);
+    if f.singular {
+        return Err(pyo3::exceptions::PyValueError::new_err("Matrix is singular"));
+    }
+    let x = Vector { data: f.solve(b) };
+    if return_cond {
+        (x, f.condition_estimate(&a)).into_py_any(py)
+    } else {
+        x.into_py_any(py)
+    }
+}
+
+// Cyclic Jacobi rotations for a symmetric matrix; returns ascending eigenvalues and the
+// eigenvectors as the columns of the second matrix
+pub fn symmetric_eigen(a: &Matrix) -> (Vec<f64>, Matrix) {
+    let n = a.rows;
+    let mut m = a.data.clone();
+    let mut v = vec![0.0; n * n];
+    for i in 0..n {
+        v[i * n + i] = 1.0;
+    }
+    let scale: f64 = m.iter().map(|x| x * x).sum::<f64>().sqrt();
+    for _sweep in 0..100 {
+        let off: f64 = (0..n).flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j))).map(|(i, j)| m[i * n + j].powi(2)).sum();
+        if off.sqrt() <= f64::EPSILON * scale {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = m[p * n + q];
+                if apq == 0.0 {
+                    continue;
+                }
+                // Rotation angle that zeroes (p, q), taking the smaller root for stability
+                let theta = (m[q * n + q] - m[p * n + p]) / (2.0 * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let t = if theta == 0.0 { 1.0 } else { t };
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (mkp, mkq) = (m[k * n + p], m[k * n + q]);
+                    m[k * n + p] = c * mkp - s * mkq;
+                    m[k * n + q] = s * mkp + c * mkq;
+                }
+                for k in 0..n {
+                    let (mpk, mqk) = (m[p * n + k], m[q * n + k]);
+                    m[p * n + k] = c * mpk - s * mqk;
+                    m[q * n + k] = s * mpk + c * mqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| m[i * n + i].total_cmp(&m[j * n + j]));
+    let values = order.iter().map(|&i| m[i * n + i]).collect();
+    let mut vectors = vec![0.0; n * n];
+    for (col, &src) in order.iter().enumerate() {
+        for row in 0..n {
+            vectors[row * n + col] = v[row * n + src];
+        }
+    }
+    (values, Matrix { rows: n, cols: n, data: vectors })
+}
+
+// Orthonormal unit vector orthogonal to every column in `basis`, by Gram-Schmidt on the
+// standard basis; used to fill out singular vectors of zero singular values
+fn orthogonal_complement(basis: &[Vec<f64>], m: usize) -> Vec<f64> {
+    for k in 0..m {
+        let mut e = vec![0.0; m];
+        e[k] = 1.0;
+        // Two passes of projection keep the result orthogonal to working precision
+        for _ in 0..2 {
+            for b in basis {
+                let d: f64 = e.iter().zip(b).map(|(x, y)| x * y).sum();
+                e.iter_mut().zip(b).for_each(|(x, y)| *x -= d * y);
+            }
+        }
+        let norm = e.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 1e-8 {
+            return e.into_iter().map(|x| x / norm).collect();
+        }
+    }
+    vec![0.0; m]
+}
+
+// Thin SVD A = U diag(S) V^T by one-sided Jacobi (Hestenes) rotations on the columns;
+// singular values come back in descending order
+pub fn thin_svd(a: &Matrix) -> (Matrix, Vec<f64>, Matrix) {
+    if a.rows < a.cols {
+        let (u, s, vt) = thin_svd(&a.transposed());
+        return (vt.transposed(), s, u.transposed());
+    }
+    let (m, n) = (a.rows, a.cols);
+    let mut u: Vec<Vec<f64>> = (0..n).map(|j| (0..m).map(|i| a.get(i, j)).collect()).collect();
+    let mut v: Vec<Vec<f64>> = (0..n).map(|j| (0..n).map(|i| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+    let rotate = |cols: &mut Vec<Vec<f64>>, p: usize, q: usize, c: f64, s: f64| {
+        let (left, right) = cols.split_at_mut(q);
+        for (x, y) in left[p].iter_mut().zip(right[0].iter_mut()) {
+            let (xp, xq) = (*x, *y);
+            *x = c * xp - s * xq;
+            *y = s * xp + c * xq;
+        }
+    };
+    for _sweep in 0..60 {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let alpha: f64 = u[p].iter().map(|x| x * x).sum();
+                let beta: f64 = u[q].iter().map(|x| x * x).sum();
+                let gamma: f64 = u[p].iter().zip(&u[q]).map(|(x, y)| x * y).sum();
+                if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() || gamma == 0.0 {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                rotate(&mut u, p, q, c, s);
+                rotate(&mut v, p, q, c, s);
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+    let sigma: Vec<f64> = u.iter().map(|col| col.iter().map(|x| x * x).sum::<f64>().sqrt()).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| sigma[j].total_cmp(&sigma[i]));
+    let tiny = sigma.iter().fold(0.0f64, |acc, &x| acc.max(x)) * m.max(n) as f64 * f64::EPSILON;
+
+    let mut u_cols: Vec<Vec<f64>> = Vec::with_capacity(n);
+    for &j in &order {
+        if sigma[j] > tiny {
+            u_cols.push(u[j].iter().map(|x| x / sigma[j]).collect());
+        }
+    }
+    while u_cols.len() < n {
+        let fill = orthogonal_complement(&u_cols, m);
+        u_cols.push(fill);
+    }
+    let values = order.iter().map(|&j| if sigma[j] > tiny { sigma[j] } else { 0.0 }).collect();
+    let mut u_mat = Matrix { rows: m, cols: n, data: vec![0.0; m * n] };
+    for (j, col) in u_cols.iter().enumerate() {
+        for (i, &x) in col.iter().enumerate() {
+            u_mat.data[i * n + j] = x;
+        }
+    }
+    let vt = Matrix { rows: n, cols: n, data: order.iter().flat_map(|&j| v[j].iter().copied()).collect() };
+    (u_mat, values, vt)
+}
+
+// Thin singular value decomposition: (U, S, V^T) with U of shape (m, k), S of length k
+// and V^T of shape (k, n), where k = min(m, n)
+#[pyfunction]
+pub fn svd(matrix: &Bound<'_, PyAny>) -> PyResult<(Matrix, Vector, Matrix)> {
+    let a = Matrix::new(matrix)?;
+    if a.data.iter().any(|v| !v.is_finite()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Matrix entries must be finite"));
+    }
+    let (u, s, vt) = thin_svd(&a);
+    Ok((u, Vector { data: s }, vt))
+}
+
+// Lower-triangular L with A = L L^T; Err carries the index of the first non-positive pivot
+pub fn cholesky_factor(a: &Matrix) -> Result<Matrix, usize> {
+    let n = a.rows;
+    let mut l = Matrix::zeros(n, n);
+    for i in 0..n {
+        for j in 0..=i {
+            let dot: f64 = l.data[i * n..i * n + j].iter().zip(&l.data[j * n..j * n + j]).map(|(x, y)| x * y).sum();
+            let value = a.get(i, j) - dot;
+            if i == j {
+                if !value.is_finite() || value <= 0.0 {
+                    return Err(i);
+                }
+                l.data[i * n + i] = value.sqrt();
+            } else {
+                l.data[i * n + j] = value / l.data[j * n + j];
+            }
+        }
+    }
+    Ok(l)
+}
+
+// Cholesky factor L (lower triangular) of a symmetric positive-definite matrix
+#[pyfunction]
+pub fn cholesky(matrix: &Bound<'_, PyAny>) -> PyResult<Matrix> {
+    let a = Matrix::new(matrix)?;
+    a.require_square()?;
+    let n = a.rows;
+    let scale = a.data.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    if (0..n).any(|i| (i + 1..n).any(|j| (a.get(i, j) - a.get(j, i)).abs() > 1e-10 * scale.max(1.0))) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Matrix must be symmetric"));
+    }
+    cholesky_factor(&a).map_err(|i| {
+        pyo3::exceptions::PyValueError::new_err(format!(
+            "Matrix is not positive definite: pivot {} is not positive (leading {}x{} minor)",
+            i,
+            i + 1,
+            i + 1
+        ))
+    })
+}
+
+// Householder QR: returns the reflectors (unit vectors v_k acting on rows k..m) and R in place
+// of A, so that Q^T A = R with Q = H_0 H_1 ... H_{k-1}
+pub fn householder(a: &Matrix) -> (Vec<Vec<f64>>, Matrix) {
+    let (m, n) = (a.rows, a.cols);
+    let mut r = a.clone();
+    let mut reflectors = Vec::with_capacity(m.min(n));
+    for k in 0..m.min(n) {
+        let mut v: Vec<f64> = (k..m).map(|i| r.data[i * n + k]).collect();
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        // Adding the norm with the sign of the pivot avoids cancellation
+        v[0] += if v[0] >= 0.0 { norm } else { -norm };
+        let v_norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if v_norm > 0.0 {
+            v.iter_mut().for_each(|x| *x /= v_norm);
+            for j in k..n {
+                let d: f64 = v.iter().enumerate().map(|(i, vi)| vi * r.data[(k + i) * n + j]).sum();
+                for (i, vi) in v.iter().enumerate() {
+                    r.data[(k + i) * n + j] -= 2.0 * vi * d;
+                }
+            }
+            for i in k + 1..m {
+                r.data[i * n + k] = 0.0;
+            }
+        }
+        reflectors.push(v);
+    }
+    (reflectors, r)
+}
+
+// Applies Q = H_0 ... H_{k-1} to the first `cols` columns of the identity
+fn form_q(reflectors: &[Vec<f64>], m: usize, cols: usize) -> Matrix {
+    let mut q = Matrix::zeros(m, cols);
+    for i in 0..cols.min(m) {
+        q.data[i * cols + i] = 1.0;
+    }
+    for (k, v) in reflectors.iter().enumerate().rev() {
+        for j in 0..cols {
+            let d: f64 = v.iter().enumerate().map(|(i, vi)| vi * q.data[(k + i) * cols + j]).sum();
+            for (i, vi) in v.iter().enumerate() {
+                q.data[(k + i) * cols + j] -= 2.0 * vi * d;
+            }
+        }
+    }
+    q
+}
+
+// Householder QR. "reduced" gives Q of shape (m, k) and R of shape (k, n) with k = min(m, n);
+// "complete" gives a square Q and R of shape (m, n)
+#[pyfunction]
+#[pyo3(signature = (matrix, mode="reduced"))]
+pub fn qr(matrix: &Bound<'_, PyAny>, mode: &str) -> PyResult<(Matrix, Matrix)> {
+    let a = Matrix::new(matrix)?;
+    if a.data.iter().any(|v| !v.is_finite()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Matrix entries must be finite"));
+    }
+    let (m, n) = (a.rows, a.cols);
+    let (reflectors, r) = householder(&a);
+    match mode {
+        "reduced" => {
+            let k = m.min(n);
+            let r = Matrix { rows: k, cols: n, data: r.data[..k * n].to_vec() };
+            Ok((form_q(&reflectors, m, k), r))
+        }
+        "complete" => Ok((form_q(&reflectors, m, m), r)),
+        _ => Err(pyo3::exceptions::PyValueError::new_err("mode must be 'reduced' or 'complete'")),
+    }
+}
+
+// Least squares min ||A x - b|| for A with at least as many rows as columns, by Householder QR:
+// x solves R x = (Q^T b)[..n] and the residual sum of squares is the squared tail of Q^T b
+#[pyfunction]
+pub fn lstsq(matrix: &Bound<'_, PyAny>, b: Rhs<'_>) -> PyResult<(Vector, f64)> {
+    let a = Matrix::new(matrix)?;
+    let (m, n) = (a.rows, a.cols);
+    let b = b.values();
+    if b.len() != m {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "b has {} elements but the matrix has {} rows",
+            b.len(),
+            m
+        )));
+    }
+    if n == 0 || m < n {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Matrix must have at least as many rows as columns, got ({}, {})",
+            m, n
+        )));
+    }
+    if a.data.iter().chain(b).any(|v| !v.is_finite()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Inputs must be finite"));
+    }
+    let (reflectors, r) = householder(&a);
+    let scale = (0..n).fold(0.0f64, |s, i| s.max(r.get(i, i).abs()));
+    if (0..n).any(|i| r.get(i, i).abs() <= scale * m as f64 * f64::EPSILON) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Matrix is rank deficient"));
+    }
+    let mut qtb = b.to_vec();
+    for (k, v) in reflectors.iter().enumerate() {
+        let d: f64 = v.iter().zip(&qtb[k..]).map(|(vi, bi)| vi * bi).sum();
+        for (bi, vi) in qtb[k..].iter_mut().zip(v) {
+            *bi -= 2.0 * vi * d;
+        }
+    }
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let s: f64 = (i + 1..n).map(|j| r.get(i, j) * x[j]).sum();
+        x[i] = (qtb[i] - s) / r.get(i, i);
+    }
+    let rss = qtb[n..].iter().fold(0.0, |s, v| s + v * v);
+    Ok((Vector { data: x }, rss))
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+use crate::options::{BsPosition, PositionTuple};
+
+// SPAN-style scan: price moves of 0, 1/3, 2/3 and 1 of the range each way with vol up and down,
+// plus two extreme moves of twice the range where only 35% of the loss counts
+fn scan_scenarios(price_range: f64, vol_range: f64) -> Vec<(f64, f64, f64)> {
+    let mut scenarios = Vec::with_capacity(16);
+    for fraction in [0.0, 1.0 / 3.0, -1.0 / 3.0, 2.0 / 3.0, -2.0 / 3.0, 1.0, -1.0] {
+        for vol_move in [vol_range, -vol_range] {
+            scenarios.push((fraction * price_range, vol_move, 1.0));
+        }
+    }
+    scenarios.push((2.0 * price_range, 0.0, 0.35));
+    scenarios.push((-2.0 * price_range, 0.0, 0.35));
+    scenarios
+}
+
+// (underlying, quantity, option_type, spot, strike, t, rate, vol)
+type MarginPosition = (String, f64, String, f64, f64, f64, f64, f64);
+
+// Positions are (underlying, quantity, option_type, spot, strike, t, rate, vol); scanning_ranges maps each
+// underlying to (relative price range, absolute vol range) and the worst scenario losses are summed
+#[pyfunction]
+pub fn initial_margin_portfolio(
+    positions: Vec<MarginPosition>,
+    scanning_ranges: HashMap<String, (f64, f64)>,
+) -> PyResult<f64> {
+    let mut books: HashMap<String, Vec<BsPosition>> = HashMap::new();
+    for (underlying, qty, option_type, spot, strike, t, rate, vol) in positions {
+        let position: PositionTuple = (qty, option_type, spot, strike, t, rate, vol);
+        books.entry(underlying).or_default().push(BsPosition::parse(position)?);
+    }
+
+    let mut margin = 0.0;
+    for (underlying, book) in &books {
+        let Some(&(price_range, vol_range)) = scanning_ranges.get(underlying) else {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "No scanning range for underlying '{}'",
+                underlying
+            )));
+        };
+        if !(0.0..1.0).contains(&price_range) || vol_range < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Price ranges must be in [0, 1) and vol ranges non-negative",
+            ));
+        }
+        let worst_loss = scan_scenarios(price_range, vol_range)
+            .into_iter()
+            .map(|(price_move, vol_move, weight)| {
+                let pnl: f64 = book.iter().map(|p| p.shocked_pnl(price_move, vol_move)).sum();
+                -pnl * weight
+            })
+            .fold(0.0, f64::max);
+        margin += worst_loss;
+    }
+    Ok(margin)
+}
+
+#[pyfunction]
+pub fn leverage_ratio(gross_exposure: f64, equity: f64) -> PyResult<f64> {
+    if equity <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Equity must be positive"));
+    }
+    if gross_exposure < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Gross exposure cannot be negative"));
+    }
+    Ok(gross_exposure / equity)
+}
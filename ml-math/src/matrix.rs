@@ -0,0 +1,284 @@
+use numpy::PyReadonlyArray2;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use pyo3::IntoPyObjectExt;
+
+use crate::input::numpy_loaded;
+use crate::linalg::{lu_factor, symmetric_eigen};
+use crate::state::{persist_methods, persist_struct};
+use crate::vector::Vector;
+
+// Right-hand side of an elementwise operator: another Matrix or a scalar
+#[derive(FromPyObject)]
+enum Operand<'py> {
+    Matrix(PyRef<'py, Matrix>),
+    Scalar(f64),
+}
+
+// Right-hand side of `@`
+#[derive(FromPyObject)]
+enum MatmulOperand<'py> {
+    Matrix(PyRef<'py, Matrix>),
+    Vector(PyRef<'py, Vector>),
+}
+
+// Dense row-major matrix; element (i, j) lives at data[i * cols + j]
+#[pyclass(module = "fina", sequence)]
+#[derive(Clone)]
+pub struct Matrix {
+    pub(crate) rows: usize,
+    pub(crate) cols: usize,
+    pub(crate) data: Vec<f64>,
+}
+
+impl Matrix {
+    pub fn from_rows(rows: &[Vec<f64>]) -> PyResult<Matrix> {
+        let cols = rows.first().map_or(0, |r| r.len());
+        if rows.iter().any(|r| r.len() != cols) {
+            return Err(pyo3::exceptions::PyValueError::new_err("Matrix rows must be equal length"));
+        }
+        Ok(Matrix { rows: rows.len(), cols, data: rows.concat() })
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<f64>> {
+        if self.cols == 0 {
+            return vec![Vec::new(); self.rows];
+        }
+        self.data.chunks(self.cols).map(|r| r.to_vec()).collect()
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        self.data[i * self.cols + j]
+    }
+
+    pub fn transposed(&self) -> Matrix {
+        let mut data = Vec::with_capacity(self.data.len());
+        for j in 0..self.cols {
+            data.extend((0..self.rows).map(|i| self.get(i, j)));
+        }
+        Matrix { rows: self.cols, cols: self.rows, data }
+    }
+
+    pub fn matmul(&self, other: &Matrix) -> PyResult<Matrix> {
+        if self.cols != other.rows {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Shape mismatch: ({}, {}) @ ({}, {})",
+                self.rows, self.cols, other.rows, other.cols
+            )));
+        }
+        // i-k-j order walks both operands row by row
+        let mut data = vec![0.0; self.rows * other.cols];
+        for (out, lhs) in data.chunks_mut(other.cols.max(1)).zip(self.data.chunks(self.cols.max(1))) {
+            for (&a, rhs) in lhs.iter().zip(other.data.chunks(other.cols.max(1))) {
+                for (o, &b) in out.iter_mut().zip(rhs) {
+                    *o += a * b;
+                }
+            }
+        }
+        Ok(Matrix { rows: self.rows, cols: other.cols, data })
+    }
+
+    fn zip_with(&self, other: Operand, op: impl Fn(f64, f64) -> f64) -> PyResult<Matrix> {
+        let data = match other {
+            Operand::Matrix(m) => {
+                if (m.rows, m.cols) != (self.rows, self.cols) {
+                    return Err(pyo3::exceptions::PyValueError::new_err("Matrices must be same shape"));
+                }
+                self.data.iter().zip(&m.data).map(|(&a, &b)| op(a, b)).collect()
+            }
+            Operand::Scalar(s) => self.data.iter().map(|&a| op(a, s)).collect(),
+        };
+        Ok(Matrix { rows: self.rows, cols: self.cols, data })
+    }
+
+    pub fn require_square(&self) -> PyResult<()> {
+        if self.rows != self.cols {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Matrix must be square, got ({}, {})",
+                self.rows, self.cols
+            )));
+        }
+        Ok(())
+    }
+
+    fn index(index: isize, len: usize) -> PyResult<usize> {
+        let n = len as isize;
+        let i = if index < 0 { index + n } else { index };
+        if i < 0 || i >= n {
+            return Err(pyo3::exceptions::PyIndexError::new_err("Matrix index out of range"));
+        }
+        Ok(i as usize)
+    }
+}
+
+persist_methods!(impl Matrix {
+    // Nested sequences or a 2-D float numpy array
+    #[new]
+    pub fn new(data: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(m) = data.cast::<Matrix>() {
+            return Ok(m.borrow().clone());
+        }
+        if numpy_loaded(data.py()) {
+            if let Ok(array) = data.extract::<PyReadonlyArray2<f64>>() {
+                let view = array.as_array();
+                let (rows, cols) = view.dim();
+                return Ok(Matrix { rows, cols, data: view.iter().copied().collect() });
+            }
+        }
+        Matrix::from_rows(&data.extract::<Vec<Vec<f64>>>()?)
+    }
+
+    #[staticmethod]
+    pub fn zeros(rows: usize, cols: usize) -> Matrix {
+        Matrix { rows, cols, data: vec![0.0; rows * cols] }
+    }
+
+    #[staticmethod]
+    pub fn identity(n: usize) -> Matrix {
+        let mut m = Matrix::zeros(n, n);
+        for i in 0..n {
+            m.data[i * n + i] = 1.0;
+        }
+        m
+    }
+
+    #[getter]
+    fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    #[getter(T)]
+    fn t(&self) -> Matrix {
+        self.transposed()
+    }
+
+    fn transpose(&self) -> Matrix {
+        self.transposed()
+    }
+
+    fn row(&self, i: isize) -> PyResult<Vector> {
+        let i = Matrix::index(i, self.rows)?;
+        Ok(Vector { data: self.data[i * self.cols..(i + 1) * self.cols].to_vec() })
+    }
+
+    fn col(&self, j: isize) -> PyResult<Vector> {
+        let j = Matrix::index(j, self.cols)?;
+        Ok(Vector { data: (0..self.rows).map(|i| self.get(i, j)).collect() })
+    }
+
+    // Determinant from the pivoted LU factorization; exactly 0.0 for singular input
+    fn det(&self) -> PyResult<f64> {
+        self.require_square()?;
+        Ok(lu_factor(self).det())
+    }
+
+    fn inverse(&self) -> PyResult<Matrix> {
+        self.require_square()?;
+        let lu = lu_factor(self);
+        if lu.singular {
+            return Err(pyo3::exceptions::PyValueError::new_err("Matrix is singular and has no inverse"));
+        }
+        let n = self.rows;
+        let mut inv = Matrix::zeros(n, n);
+        let mut unit = vec![0.0; n];
+        for j in 0..n {
+            unit[j] = 1.0;
+            for (i, v) in lu.solve(&unit).into_iter().enumerate() {
+                inv.data[i * n + j] = v;
+            }
+            unit[j] = 0.0;
+        }
+        Ok(inv)
+    }
+
+    // Symmetric eigendecomposition by Jacobi rotations: (ascending eigenvalues, eigenvectors as columns)
+    fn eigh(&self) -> PyResult<(Vector, Matrix)> {
+        self.require_square()?;
+        let n = self.rows;
+        let scale = self.data.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        let asymmetric = (0..n).any(|i| (i + 1..n).any(|j| (self.get(i, j) - self.get(j, i)).abs() > 1e-10 * scale.max(1.0)));
+        if asymmetric {
+            return Err(pyo3::exceptions::PyValueError::new_err("Matrix must be symmetric"));
+        }
+        let (values, vectors) = symmetric_eigen(self);
+        Ok((Vector { data: values }, vectors))
+    }
+
+    fn __len__(&self) -> usize {
+        self.rows
+    }
+
+    // m[i] is row i as a Vector, m[i, j] a single element
+    fn __getitem__(&self, py: Python<'_>, index: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        if index.is_instance_of::<PyTuple>() {
+            let (i, j): (isize, isize) = index.extract()?;
+            let (i, j) = (Matrix::index(i, self.rows)?, Matrix::index(j, self.cols)?);
+            return self.get(i, j).into_py_any(py);
+        }
+        self.row(index.extract()?)?.into_py_any(py)
+    }
+
+    fn __setitem__(&mut self, index: (isize, isize), value: f64) -> PyResult<()> {
+        let (i, j) = (Matrix::index(index.0, self.rows)?, Matrix::index(index.1, self.cols)?);
+        self.data[i * self.cols + j] = value;
+        Ok(())
+    }
+
+    fn __matmul__(&self, py: Python<'_>, other: MatmulOperand) -> PyResult<Py<PyAny>> {
+        match other {
+            MatmulOperand::Matrix(m) => self.matmul(&m)?.into_py_any(py),
+            MatmulOperand::Vector(v) => {
+                let column = Matrix { rows: v.data.len(), cols: 1, data: v.data.clone() };
+                Vector { data: self.matmul(&column)?.data }.into_py_any(py)
+            }
+        }
+    }
+
+    fn __add__(&self, other: Operand) -> PyResult<Matrix> {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    fn __radd__(&self, other: Operand) -> PyResult<Matrix> {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    fn __sub__(&self, other: Operand) -> PyResult<Matrix> {
+        self.zip_with(other, |a, b| a - b)
+    }
+
+    fn __rsub__(&self, other: Operand) -> PyResult<Matrix> {
+        self.zip_with(other, |a, b| b - a)
+    }
+
+    // Hadamard product with a Matrix, scaling with a number; use @ for the matrix product
+    fn __mul__(&self, other: Operand) -> PyResult<Matrix> {
+        self.zip_with(other, |a, b| a * b)
+    }
+
+    fn __rmul__(&self, other: Operand) -> PyResult<Matrix> {
+        self.zip_with(other, |a, b| a * b)
+    }
+
+    fn __truediv__(&self, other: Operand) -> PyResult<Matrix> {
+        self.zip_with(other, |a, b| a / b)
+    }
+
+    fn __neg__(&self) -> Matrix {
+        Matrix { rows: self.rows, cols: self.cols, data: self.data.iter().map(|x| -x).collect() }
+    }
+
+    fn __eq__(&self, other: PyRef<Matrix>) -> bool {
+        (self.rows, self.cols) == (other.rows, other.cols) && self.data == other.data
+    }
+
+    fn tolist(&self) -> Vec<Vec<f64>> {
+        self.to_rows()
+    }
+
+    fn __repr__(&self) -> String {
+        let rows: Vec<String> = self.to_rows().iter().map(|r| format!("{:?}", r)).collect();
+        format!("Matrix([{}])", rows.join(", "))
+    }
+});
+
+persist_struct!(Matrix { rows, cols, data }, check = |m| m.rows.checked_mul(m.cols) == Some(m.data.len()));
@@ -0,0 +1,452 @@
+use pyo3::prelude::*;
+
+// Solvers work on any fallible scalar function, so native callers and Python callables share them
+
+pub fn brent_root<F>(mut f: F, mut a: f64, mut b: f64, tol: f64, max_iter: usize) -> PyResult<f64>
+where
+    F: FnMut(f64) -> PyResult<f64>,
+{
+    let mut fa = f(a)?;
+    let mut fb = f(b)?;
+    if fa == 0.0 {
+        return Ok(a);
+    }
+    if fb == 0.0 {
+        return Ok(b);
+    }
+    if fa.signum() == fb.signum() {
+        return Err(pyo3::exceptions::PyValueError::new_err("f(a) and f(b) must have opposite signs"));
+    }
+    if fa.abs() < fb.abs() {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut fa, &mut fb);
+    }
+
+    let mut c = a;
+    let mut fc = fa;
+    let mut d = b - a;
+    let mut bisected = true;
+    for _ in 0..max_iter {
+        if fb == 0.0 || (b - a).abs() < tol {
+            return Ok(b);
+        }
+        // Inverse quadratic interpolation when possible, secant otherwise
+        let mut s = if fa != fc && fb != fc {
+            a * fb * fc / ((fa - fb) * (fa - fc)) + b * fa * fc / ((fb - fa) * (fb - fc)) + c * fa * fb / ((fc - fa) * (fc - fb))
+        } else {
+            b - fb * (b - a) / (fb - fa)
+        };
+        let bound = (3.0 * a + b) / 4.0;
+        let out_of_range = !((s > bound.min(b)) && (s < bound.max(b)));
+        let slow = if bisected { (s - b).abs() >= (b - c).abs() / 2.0 } else { (s - b).abs() >= (c - d).abs() / 2.0 };
+        let tiny = if bisected { (b - c).abs() < tol } else { (c - d).abs() < tol };
+        if out_of_range || slow || tiny {
+            s = (a + b) / 2.0;
+            bisected = true;
+        } else {
+            bisected = false;
+        }
+        let fs = f(s)?;
+        d = c;
+        c = b;
+        fc = fb;
+        if fa.signum() != fs.signum() {
+            b = s;
+            fb = fs;
+        } else {
+            a = s;
+            fa = fs;
+        }
+        if fa.abs() < fb.abs() {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut fa, &mut fb);
+        }
+    }
+    Err(pyo3::exceptions::PyRuntimeError::new_err("brentq failed to converge"))
+}
+
+pub fn newton_root<F, G>(mut f: F, mut grad: Option<G>, x0: f64, tol: f64, max_iter: usize) -> PyResult<f64>
+where
+    F: FnMut(f64) -> PyResult<f64>,
+    G: FnMut(f64) -> PyResult<f64>,
+{
+    let mut x = x0;
+    // Without a derivative, fall back to the secant method
+    let mut prev_x = x0 * (1.0 + 1e-4) + if x0 >= 0.0 { 1e-4 } else { -1e-4 };
+    let mut prev_f = f(prev_x)?;
+    for _ in 0..max_iter {
+        let fx = f(x)?;
+        if fx == 0.0 {
+            return Ok(x);
+        }
+        let slope = match grad.as_mut() {
+            Some(g) => g(x)?,
+            None => (fx - prev_f) / (x - prev_x),
+        };
+        if slope == 0.0 || !slope.is_finite() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err("Derivative was zero or not finite"));
+        }
+        let next = x - fx / slope;
+        prev_x = x;
+        prev_f = fx;
+        x = next;
+        if (x - prev_x).abs() < tol {
+            return Ok(x);
+        }
+    }
+    Err(pyo3::exceptions::PyRuntimeError::new_err("newton failed to converge"))
+}
+
+pub fn golden_section<F>(mut f: F, mut a: f64, mut b: f64, tol: f64, max_iter: usize) -> PyResult<(f64, f64)>
+where
+    F: FnMut(f64) -> PyResult<f64>,
+{
+    let inv_phi = (5f64.sqrt() - 1.0) / 2.0;
+    let mut c = b - inv_phi * (b - a);
+    let mut d = a + inv_phi * (b - a);
+    let mut fc = f(c)?;
+    let mut fd = f(d)?;
+    for _ in 0..max_iter {
+        if (b - a).abs() < tol {
+            break;
+        }
+        if fc < fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - inv_phi * (b - a);
+            fc = f(c)?;
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + inv_phi * (b - a);
+            fd = f(d)?;
+        }
+    }
+    let x = (a + b) / 2.0;
+    Ok((x, f(x)?))
+}
+
+fn call_scalar(func: &Bound<'_, PyAny>, x: f64) -> PyResult<f64> {
+    func.call1((x,))?.extract::<f64>()
+}
+
+// The callables need the GIL on every evaluation, so it stays held; the solver bookkeeping
+// between calls is a handful of flops and gains nothing from being detached.
+#[pyfunction]
+#[pyo3(signature = (func, a, b, tol=1e-12, max_iter=100))]
+pub fn brentq(func: &Bound<'_, PyAny>, a: f64, b: f64, tol: f64, max_iter: usize) -> PyResult<f64> {
+    if a >= b {
+        return Err(pyo3::exceptions::PyValueError::new_err("a must be smaller than b"));
+    }
+    brent_root(|x| call_scalar(func, x), a, b, tol, max_iter)
+}
+
+#[pyfunction]
+#[pyo3(signature = (func, x0, grad=None, tol=1e-12, max_iter=50))]
+pub fn newton(func: &Bound<'_, PyAny>, x0: f64, grad: Option<&Bound<'_, PyAny>>, tol: f64, max_iter: usize) -> PyResult<f64> {
+    newton_root(|x| call_scalar(func, x), grad.map(|g| move |x| call_scalar(g, x)), x0, tol, max_iter)
+}
+
+#[pyfunction]
+#[pyo3(signature = (func, a, b, tol=1e-10, max_iter=200))]
+pub fn golden_section_minimize(func: &Bound<'_, PyAny>, a: f64, b: f64, tol: f64, max_iter: usize) -> PyResult<(f64, f64)> {
+    if a >= b {
+        return Err(pyo3::exceptions::PyValueError::new_err("a must be smaller than b"));
+    }
+    golden_section(|x| call_scalar(func, x), a, b, tol, max_iter)
+}
+
+#[pyclass(module = "fina")]
+#[derive(Clone)]
+pub struct OptimizeResult {
+    #[pyo3(get)]
+    pub x: Vec<f64>,
+    #[pyo3(get)]
+    pub fun: f64,
+    #[pyo3(get)]
+    pub n_iter: usize,
+    #[pyo3(get)]
+    pub n_eval: usize,
+    #[pyo3(get)]
+    pub converged: bool,
+    #[pyo3(get)]
+    pub message: String,
+}
+
+#[pymethods]
+impl OptimizeResult {
+    fn __repr__(&self) -> String {
+        format!("OptimizeResult(fun={}, x={:?}, n_iter={}, converged={})", self.fun, self.x, self.n_iter, self.converged)
+    }
+}
+
+fn project(x: &mut [f64], bounds: Option<&[(f64, f64)]>) {
+    if let Some(bounds) = bounds {
+        for (v, &(lo, hi)) in x.iter_mut().zip(bounds) {
+            *v = v.clamp(lo, hi);
+        }
+    }
+}
+
+pub fn nelder_mead<F>(mut f: F, x0: &[f64], bounds: Option<&[(f64, f64)]>, tol: f64, max_iter: usize) -> PyResult<OptimizeResult>
+where
+    F: FnMut(&[f64]) -> PyResult<f64>,
+{
+    let n = x0.len();
+    let mut n_eval = 0;
+    let mut eval = |x: &[f64], n_eval: &mut usize| -> PyResult<f64> {
+        *n_eval += 1;
+        let v = f(x)?;
+        Ok(if v.is_nan() { f64::INFINITY } else { v })
+    };
+
+    // Initial simplex: 5% steps along each axis (0.00025 for zero coordinates), as scipy does
+    let mut simplex: Vec<Vec<f64>> = vec![x0.to_vec()];
+    for i in 0..n {
+        let mut v = x0.to_vec();
+        v[i] = if v[i] != 0.0 { v[i] * 1.05 } else { 0.00025 };
+        simplex.push(v);
+    }
+    for v in simplex.iter_mut() {
+        project(v, bounds);
+    }
+    let mut values = Vec::with_capacity(n + 1);
+    for v in &simplex {
+        values.push(eval(v, &mut n_eval)?);
+    }
+
+    let mut converged = false;
+    let mut iter = 0;
+    while iter < max_iter {
+        iter += 1;
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        values = order.iter().map(|&i| values[i]).collect();
+
+        let f_spread = values.iter().map(|v| (v - values[0]).abs()).fold(0.0, f64::max);
+        let x_spread = simplex[1..]
+            .iter()
+            .flat_map(|v| v.iter().zip(&simplex[0]).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+        if f_spread <= tol && x_spread <= tol {
+            converged = true;
+            break;
+        }
+
+        let centroid: Vec<f64> = (0..n).map(|j| simplex[..n].iter().map(|v| v[j]).sum::<f64>() / n as f64).collect();
+        let towards = |coef: f64| -> Vec<f64> {
+            let mut p: Vec<f64> = centroid.iter().zip(&simplex[n]).map(|(c, w)| c + coef * (c - w)).collect();
+            project(&mut p, bounds);
+            p
+        };
+
+        let reflected = towards(1.0);
+        let f_reflected = eval(&reflected, &mut n_eval)?;
+        if f_reflected < values[0] {
+            let expanded = towards(2.0);
+            let f_expanded = eval(&expanded, &mut n_eval)?;
+            if f_expanded < f_reflected {
+                simplex[n] = expanded;
+                values[n] = f_expanded;
+            } else {
+                simplex[n] = reflected;
+                values[n] = f_reflected;
+            }
+        } else if f_reflected < values[n - 1] {
+            simplex[n] = reflected;
+            values[n] = f_reflected;
+        } else {
+            let outside = f_reflected < values[n];
+            let contracted = towards(if outside { 0.5 } else { -0.5 });
+            let f_contracted = eval(&contracted, &mut n_eval)?;
+            if f_contracted < if outside { f_reflected } else { values[n] } {
+                simplex[n] = contracted;
+                values[n] = f_contracted;
+            } else {
+                // Shrink everything towards the best vertex
+                for i in 1..=n {
+                    let shrunk: Vec<f64> = simplex[0].iter().zip(&simplex[i]).map(|(b, v)| b + 0.5 * (v - b)).collect();
+                    values[i] = eval(&shrunk, &mut n_eval)?;
+                    simplex[i] = shrunk;
+                }
+            }
+        }
+    }
+
+    let best = (0..=n).fold(0, |b, i| if values[i] < values[b] { i } else { b });
+    Ok(OptimizeResult {
+        x: simplex[best].clone(),
+        fun: values[best],
+        n_iter: iter,
+        n_eval,
+        converged,
+        message: if converged { "Converged".to_string() } else { "Maximum iterations reached".to_string() },
+    })
+}
+
+// Central finite-difference gradient
+pub fn numeric_gradient<F>(f: &mut F, x: &[f64]) -> PyResult<Vec<f64>>
+where
+    F: FnMut(&[f64]) -> PyResult<f64>,
+{
+    let mut grad = vec![0.0; x.len()];
+    let mut probe = x.to_vec();
+    for i in 0..x.len() {
+        let h = 1e-6 * x[i].abs().max(1.0);
+        probe[i] = x[i] + h;
+        let up = f(&probe)?;
+        probe[i] = x[i] - h;
+        let down = f(&probe)?;
+        probe[i] = x[i];
+        grad[i] = (up - down) / (2.0 * h);
+    }
+    Ok(grad)
+}
+
+pub fn lbfgs<F, G>(mut f: F, mut grad: G, x0: &[f64], bounds: Option<&[(f64, f64)]>, tol: f64, max_iter: usize) -> PyResult<OptimizeResult>
+where
+    F: FnMut(&[f64]) -> PyResult<f64>,
+    G: FnMut(&mut F, &[f64]) -> PyResult<Vec<f64>>,
+{
+    const MEMORY: usize = 10;
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+
+    let mut x = x0.to_vec();
+    project(&mut x, bounds);
+    let mut fx = f(&x)?;
+    let mut g = grad(&mut f, &x)?;
+    let mut n_eval = 1;
+    let mut history: std::collections::VecDeque<(Vec<f64>, Vec<f64>, f64)> = std::collections::VecDeque::new();
+    let mut converged = false;
+    let mut message = "Maximum iterations reached".to_string();
+    let mut iter = 0;
+
+    while iter < max_iter {
+        if g.iter().fold(0.0f64, |m, v| m.max(v.abs())) < tol {
+            converged = true;
+            message = "Gradient below tolerance".to_string();
+            break;
+        }
+        iter += 1;
+
+        // Two-loop recursion for the quasi-Newton direction
+        let mut q = g.clone();
+        let mut alphas = Vec::with_capacity(history.len());
+        for (s, y, rho) in history.iter().rev() {
+            let a = rho * dot(s, &q);
+            q.iter_mut().zip(y).for_each(|(qi, yi)| *qi -= a * yi);
+            alphas.push(a);
+        }
+        if let Some((s, y, _)) = history.back() {
+            let gamma = dot(s, y) / dot(y, y);
+            q.iter_mut().for_each(|qi| *qi *= gamma);
+        }
+        for ((s, y, rho), a) in history.iter().zip(alphas.iter().rev()) {
+            let b = rho * dot(y, &q);
+            q.iter_mut().zip(s).for_each(|(qi, si)| *qi += (a - b) * si);
+        }
+        let mut direction: Vec<f64> = q.iter().map(|v| -v).collect();
+        if dot(&direction, &g) >= 0.0 {
+            direction = g.iter().map(|v| -v).collect();
+            history.clear();
+        }
+
+        // Weak Wolfe line search by bracketing and bisection on the projected step
+        let (mut lo, mut hi, mut step) = (0.0, f64::INFINITY, 1.0);
+        let mut accepted = None;
+        for _ in 0..60 {
+            let mut candidate: Vec<f64> = x.iter().zip(&direction).map(|(xi, di)| xi + step * di).collect();
+            project(&mut candidate, bounds);
+            let moved: Vec<f64> = candidate.iter().zip(&x).map(|(c, xi)| c - xi).collect();
+            let slope = dot(&g, &moved);
+            let f_candidate = f(&candidate)?;
+            n_eval += 1;
+            if !f_candidate.is_finite() || f_candidate > fx + 1e-4 * slope {
+                hi = step;
+            } else {
+                let g_candidate = grad(&mut f, &candidate)?;
+                if dot(&g_candidate, &moved) < 0.9 * slope {
+                    lo = step;
+                } else {
+                    accepted = Some((candidate, f_candidate, g_candidate));
+                    break;
+                }
+            }
+            step = if hi.is_finite() { (lo + hi) / 2.0 } else { 2.0 * lo.max(0.5) };
+        }
+        let Some((x_new, f_new, g_new)) = accepted else {
+            message = "Line search failed".to_string();
+            break;
+        };
+
+        let s: Vec<f64> = x_new.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let y: Vec<f64> = g_new.iter().zip(&g).map(|(a, b)| a - b).collect();
+        let sy = dot(&s, &y);
+        if sy > 1e-12 {
+            if history.len() == MEMORY {
+                history.pop_front();
+            }
+            history.push_back((s, y, 1.0 / sy));
+        }
+
+        let f_change = (fx - f_new).abs();
+        x = x_new;
+        g = g_new;
+        fx = f_new;
+        if f_change <= tol * fx.abs().max(1.0) {
+            converged = true;
+            message = "Function change below tolerance".to_string();
+            break;
+        }
+    }
+
+    Ok(OptimizeResult { x, fun: fx, n_iter: iter, n_eval, converged, message })
+}
+
+#[pyfunction]
+#[pyo3(signature = (objective, x0, method="nelder-mead", bounds=None, tol=1e-8, max_iter=1000, grad=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn minimize(
+    objective: &Bound<'_, PyAny>,
+    x0: Vec<f64>,
+    method: &str,
+    bounds: Option<Vec<(f64, f64)>>,
+    tol: f64,
+    max_iter: usize,
+    grad: Option<&Bound<'_, PyAny>>,
+) -> PyResult<OptimizeResult> {
+    if x0.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("x0 cannot be empty"));
+    }
+    if let Some(b) = &bounds {
+        if b.len() != x0.len() || b.iter().any(|(lo, hi)| lo > hi) {
+            return Err(pyo3::exceptions::PyValueError::new_err("bounds must give one (low, high) pair per parameter"));
+        }
+    }
+    let call = |x: &[f64]| -> PyResult<f64> { objective.call1((x.to_vec(),))?.extract::<f64>() };
+
+    match method {
+        "nelder-mead" => nelder_mead(call, &x0, bounds.as_deref(), tol, max_iter),
+        "lbfgs" => match grad {
+            Some(g) => lbfgs(
+                call,
+                |_: &mut _, x: &[f64]| -> PyResult<Vec<f64>> {
+                    let out: Vec<f64> = g.call1((x.to_vec(),))?.extract()?;
+                    if out.len() != x.len() {
+                        return Err(pyo3::exceptions::PyValueError::new_err("grad must return one value per parameter"));
+                    }
+                    Ok(out)
+                },
+                &x0,
+                bounds.as_deref(),
+                tol,
+                max_iter,
+            ),
+            None => lbfgs(call, numeric_gradient, &x0, bounds.as_deref(), tol, max_iter),
+        },
+        _ => Err(pyo3::exceptions::PyValueError::new_err("method must be 'nelder-mead' or 'lbfgs'")),
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use pyo3::prelude::*;
+
+use crate::options::norm_cdf;
+use crate::state::{persist_methods, Persist, Reader, Writer};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+fn parse_side(side: &str) -> PyResult<Side> {
+    match side {
+        "buy" => Ok(Side::Buy),
+        "sell" => Ok(Side::Sell),
+        _ => Err(pyo3::exceptions::PyValueError::new_err("side must be 'buy' or 'sell'")),
+    }
+}
+
+// (maker_id, price, qty)
+type Fill = (u64, f64, f64);
+// (price, aggregated qty)
+type Level = (f64, f64);
+
+struct Resting {
+    id: u64,
+    qty: f64,
+}
+
+// Price-time priority matching engine; prices are held as integer ticks so levels compare exactly
+#[pyclass(module = "fina")]
+pub struct OrderBookSim {
+    tick_size: f64,
+    bids: BTreeMap<i64, VecDeque<Resting>>,
+    asks: BTreeMap<i64, VecDeque<Resting>>,
+    index: HashMap<u64, (Side, i64)>,
+    next_id: u64,
+}
+
+impl OrderBookSim {
+    fn to_ticks(&self, price: f64) -> PyResult<i64> {
+        if !(price.is_finite() && price > 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err("Price must be positive and finite"));
+        }
+        let tick = (price / self.tick_size).round() as i64;
+        if tick <= 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Price {} rounds to a non-positive tick at tick size {}",
+                price, self.tick_size
+            )));
+        }
+        Ok(tick)
+    }
+
+    // Dividing by the integer-ish inverse keeps decimal ticks like 0.01 printing cleanly
+    fn to_price(&self, tick: i64) -> f64 {
+        tick as f64 / (1.0 / self.tick_size)
+    }
+
+    // Fill against the opposite side up to an optional limit; returns the fills and unfilled quantity
+    fn match_order(&mut self, side: Side, mut qty: f64, limit: Option<i64>) -> (Vec<Fill>, f64) {
+        let mut fills = Vec::new();
+        while qty > 0.0 {
+            let best = match side {
+                Side::Buy => self.asks.keys().next().copied(),
+                Side::Sell => self.bids.keys().next_back().copied(),
+            };
+            let Some(level) = best else { break };
+            let crosses = match (side, limit) {
+                (_, None) => true,
+                (Side::Buy, Some(l)) => level <= l,
+                (Side::Sell, Some(l)) => level >= l,
+            };
+            if !crosses {
+                break;
+            }
+            let price = self.to_price(level);
+            let book = match side {
+                Side::Buy => &mut self.asks,
+                Side::Sell => &mut self.bids,
+            };
+            let queue = book.get_mut(&level).unwrap();
+            while qty > 0.0 {
+                let Some(front) = queue.front_mut() else { break };
+                let traded = qty.min(front.qty);
+                fills.push((front.id, price, traded));
+                qty -= traded;
+                front.qty -= traded;
+                if front.qty <= 0.0 {
+                    let id = front.id;
+                    queue.pop_front();
+                    self.index.remove(&id);
+                }
+            }
+            if queue.is_empty() {
+                book.remove(&level);
+            }
+        }
+        (fills, qty)
+    }
+
+    fn levels<'a>(&self, iter: impl Iterator<Item = (&'a i64, &'a VecDeque<Resting>)>, depth: usize) -> Vec<Level> {
+        iter.take(depth)
+            .map(|(&tick, queue)| (self.to_price(tick), queue.iter().map(|o| o.qty).sum()))
+            .collect()
+    }
+}
+
+persist_methods!(impl OrderBookSim {
+    #[new]
+    #[pyo3(signature = (tick_size=0.01))]
+    fn new(tick_size: f64) -> PyResult<Self> {
+        if !tick_size.is_finite() || tick_size <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("tick_size must be positive"));
+        }
+        Ok(OrderBookSim {
+            tick_size,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            index: HashMap::new(),
+            next_id: 1,
+        })
+    }
+
+    // Crossing quantity trades immediately; any remainder rests at the limit price
+    fn add_limit(&mut self, side: &str, price: f64, qty: f64) -> PyResult<(u64, Vec<Fill>)> {
+        let side = parse_side(side)?;
+        let tick = self.to_ticks(price)?;
+        if !qty.is_finite() || qty <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("Quantity must be positive"));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let (fills, remaining) = self.match_order(side, qty, Some(tick));
+        if remaining > 0.0 {
+            let book = match side {
+                Side::Buy => &mut self.bids,
+                Side::Sell => &mut self.asks,
+            };
+            book.entry(tick).or_default().push_back(Resting { id, qty: remaining });
+            self.index.insert(id, (side, tick));
+        }
+        Ok((id, fills))
+    }
+
+    // Unfilled market quantity is dropped rather than rested
+    fn add_market(&mut self, side: &str, qty: f64) -> PyResult<Vec<Fill>> {
+        let side = parse_side(side)?;
+        if !qty.is_finite() || qty <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("Quantity must be positive"));
+        }
+        Ok(self.match_order(side, qty, None).0)
+    }
+
+    fn cancel(&mut self, order_id: u64) -> bool {
+        let Some((side, tick)) = self.index.remove(&order_id) else {
+            return false;
+        };
+        let book = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        if let Some(queue) = book.get_mut(&tick) {
+            queue.retain(|o| o.id != order_id);
+            if queue.is_empty() {
+                book.remove(&tick);
+            }
+        }
+        true
+    }
+
+    // Aggregated (price, qty) levels, best first
+    #[pyo3(signature = (depth=5))]
+    fn snapshot(&self, depth: usize) -> (Vec<Level>, Vec<Level>) {
+        (self.levels(self.bids.iter().rev(), depth), self.levels(self.asks.iter(), depth))
+    }
+
+    #[getter]
+    fn best_bid(&self) -> Option<f64> {
+        self.bids.keys().next_back().map(|&t| self.to_price(t))
+    }
+
+    #[getter]
+    fn best_ask(&self) -> Option<f64> {
+        self.asks.keys().next().map(|&t| self.to_price(t))
+    }
+
+    #[getter]
+    fn open_orders(&self) -> usize {
+        self.index.len()
+    }
+});
+
+// Volume still ahead of a resting order after each interval: trades consume the queue from the front,
+// and a fixed fraction of each interval's cancelled volume is assumed to sit ahead of us
+#[pyfunction]
+#[pyo3(signature = (order_entry_volume_ahead, traded_volume, cancels, cancel_ahead_fraction=0.5))]
+pub fn queue_position(
+    order_entry_volume_ahead: f64,
+    traded_volume: Vec<f64>,
+    cancels: Vec<f64>,
+    cancel_ahead_fraction: f64,
+) -> PyResult<Vec<f64>> {
+    if traded_volume.len() != cancels.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "traded_volume and cancels must have equal lengths",
+        ));
+    }
+    let valid = |v: f64| v.is_finite() && v >= 0.0;
+    if !valid(order_entry_volume_ahead) || !traded_volume.iter().chain(cancels.iter()).all(|&v| valid(v)) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Volumes must be finite and non-negative"));
+    }
+    if !(valid(cancel_ahead_fraction) && cancel_ahead_fraction <= 1.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("cancel_ahead_fraction must be in [0, 1]"));
+    }
+    let mut ahead = order_entry_volume_ahead;
+    Ok(traded_volume
+        .iter()
+        .zip(cancels.iter())
+        .map(|(traded, cancelled)| {
+            ahead = (ahead - traded - cancel_ahead_fraction * cancelled).max(0.0);
+            ahead
+        })
+        .collect())
+}
+
+// Chance that driftless Brownian price motion touches a level distance_ticks away within the horizon,
+// by the reflection principle; volatility is in ticks per square root of the horizon's time unit
+#[pyfunction]
+pub fn fill_probability(distance_ticks: f64, volatility: f64, horizon: f64) -> PyResult<f64> {
+    if distance_ticks < 0.0 || volatility < 0.0 || horizon < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "distance_ticks, volatility and horizon cannot be negative",
+        ));
+    }
+    if distance_ticks == 0.0 {
+        return Ok(1.0);
+    }
+    let sd = volatility * horizon.sqrt();
+    if sd == 0.0 {
+        return Ok(0.0);
+    }
+    Ok(2.0 * (1.0 - norm_cdf(distance_ticks / sd)))
+}
+
+type SavedSide = Vec<(i64, Vec<(u64, f64)>)>;
+
+fn save_side(levels: &BTreeMap<i64, VecDeque<Resting>>) -> SavedSide {
+    levels.iter().map(|(&tick, queue)| (tick, queue.iter().map(|o| (o.id, o.qty)).collect())).collect()
+}
+
+// Queue order within each level is preserved; the id index is rebuilt on load
+impl Persist for OrderBookSim {
+    const NAME: &'static str = "OrderBookSim";
+
+    fn save(&self, w: &mut Writer) {
+        self.tick_size.save(w);
+        self.next_id.save(w);
+        save_side(&self.bids).save(w);
+        save_side(&self.asks).save(w);
+    }
+
+    fn load(r: &mut Reader) -> PyResult<Self> {
+        let tick_size = f64::load(r)?;
+        let next_id = u64::load(r)?;
+        if !tick_size.is_finite() || tick_size <= 0.0 {
+            return Err(crate::state::corrupt());
+        }
+        let mut book = OrderBookSim { tick_size, bids: BTreeMap::new(), asks: BTreeMap::new(), index: HashMap::new(), next_id };
+        for side in [Side::Buy, Side::Sell] {
+            for (tick, orders) in SavedSide::load(r)? {
+                let mut queue = VecDeque::with_capacity(orders.len());
+                for (id, qty) in orders {
+                    if id >= next_id || book.index.insert(id, (side, tick)).is_some() {
+                        return Err(crate::state::corrupt());
+                    }
+                    queue.push_back(Resting { id, qty });
+                }
+                let levels = if side == Side::Buy { &mut book.bids } else { &mut book.asks };
+                levels.insert(tick, queue);
+            }
+        }
+        Ok(book)
+    }
+}
+
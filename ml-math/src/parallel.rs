@@ -0,0 +1,103 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::input::Element;
+
+// Inputs at least this long are split across the rayon thread pool
+static PARALLEL_THRESHOLD: AtomicUsize = AtomicUsize::new(500_000);
+
+pub fn parallel(len: usize) -> bool {
+    len >= PARALLEL_THRESHOLD.load(Ordering::Relaxed)
+}
+
+#[pyfunction]
+pub fn set_parallel_threshold(n_elements: usize) {
+    PARALLEL_THRESHOLD.store(n_elements, Ordering::Relaxed);
+}
+
+#[pyfunction]
+pub fn get_parallel_threshold() -> usize {
+    PARALLEL_THRESHOLD.load(Ordering::Relaxed)
+}
+
+// Reductions sum fixed-size chunks one after another and then add the chunk totals in order, on
+// both paths, so a result is bit-for-bit the same whatever the thread count or threshold
+pub const CHUNK: usize = 4096;
+
+pub fn sum_map<T: Element>(data: &[T], f: impl Fn(f64) -> f64 + Sync + Send) -> f64 {
+    let chunk_sum = |chunk: &[T]| chunk.iter().map(|x| f(x.to_f64())).sum::<f64>();
+    if parallel(data.len()) {
+        data.par_chunks(CHUNK).map(chunk_sum).collect::<Vec<f64>>().into_iter().sum()
+    } else {
+        data.chunks(CHUNK).map(chunk_sum).sum()
+    }
+}
+
+pub fn sum_zip<A: Element, B: Element>(a: &[A], b: &[B], f: impl Fn(f64, f64) -> f64 + Sync + Send) -> f64 {
+    let chunk_sum = |(a, b): (&[A], &[B])| a.iter().zip(b).map(|(x, y)| f(x.to_f64(), y.to_f64())).sum::<f64>();
+    if parallel(a.len()) {
+        a.par_chunks(CHUNK).zip(b.par_chunks(CHUNK)).map(chunk_sum).collect::<Vec<f64>>().into_iter().sum()
+    } else {
+        a.chunks(CHUNK).zip(b.chunks(CHUNK)).map(chunk_sum).sum()
+    }
+}
+
+pub fn map_collect<T: Element>(data: &[T], f: impl Fn(f64) -> f64 + Sync + Send) -> Vec<f64> {
+    if parallel(data.len()) {
+        data.par_iter().map(|x| f(x.to_f64())).collect()
+    } else {
+        data.iter().map(|x| f(x.to_f64())).collect()
+    }
+}
+
+pub fn min_max<T: Element>(data: &[T]) -> (f64, f64) {
+    let merge = |(lo, hi): (f64, f64), (a, b): (f64, f64)| (lo.min(a), hi.max(b));
+    let empty = (f64::INFINITY, f64::NEG_INFINITY);
+    let chunk_extremes = |chunk: &[T]| chunk.iter().map(|x| (x.to_f64(), x.to_f64())).fold(empty, merge);
+    if parallel(data.len()) {
+        data.par_chunks(CHUNK).map(chunk_extremes).collect::<Vec<_>>().into_iter().fold(empty, merge)
+    } else {
+        data.chunks(CHUNK).map(chunk_extremes).fold(empty, merge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_and_sequential_reductions_agree_at_the_threshold() {
+        let n = get_parallel_threshold();
+        assert!(parallel(n) && !parallel(n - 1));
+        let a: Vec<f64> = (0..n).map(|i| (i * 7919 % 1000) as f64 / 7.0 - 60.0).collect();
+        let b: Vec<f32> = (0..n).map(|i| (i * 104729 % 997) as f32 / 13.0).collect();
+        let close = |x: f64, y: f64| (x - y).abs() <= 1e-12 * y.abs().max(1.0);
+
+        // The full slice takes the parallel path, one element less the sequential one
+        let squares: f64 = a.iter().map(|x| x * x).sum();
+        assert!(close(sum_map(&a, |x| x * x), squares));
+        assert!(close(sum_map(&a[..n - 1], |x| x * x) + a[n - 1] * a[n - 1], squares));
+
+        let dot: f64 = a.iter().zip(&b).map(|(x, y)| x * *y as f64).sum();
+        assert!(close(sum_zip(&a, &b, |x, y| x * y), dot));
+        assert!(close(sum_zip(&a[..n - 1], &b[..n - 1], |x, y| x * y) + a[n - 1] * b[n - 1] as f64, dot));
+
+        let extremes = b.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| (lo.min(x as f64), hi.max(x as f64)));
+        assert_eq!(min_max(&b), extremes);
+        assert_eq!(map_collect(&a, f64::abs)[..n - 1], map_collect(&a[..n - 1], f64::abs)[..]);
+    }
+
+    #[test]
+    fn parallel_sums_are_independent_of_scheduling() {
+        let n = get_parallel_threshold() + 12345;
+        let a: Vec<f64> = (0..n).map(|i| 1.0 / (1.0 + i as f64) - 1e-7 * (i % 31) as f64).collect();
+        // The parallel path must give exactly the chunk-ordered sequential sum, on any thread count
+        let chunked: f64 = a.chunks(CHUNK).map(|c| c.iter().map(|x| x * 3.0).sum::<f64>()).sum();
+        assert_eq!(sum_map(&a, |x| x * 3.0).to_bits(), chunked.to_bits());
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+        assert_eq!(pool.install(|| sum_map(&a, |x| x * 3.0)).to_bits(), chunked.to_bits());
+        assert_eq!(pool.install(|| sum_zip(&a, &a, |x, y| x * y)).to_bits(), sum_zip(&a, &a, |x, y| x * y).to_bits());
+    }
+}
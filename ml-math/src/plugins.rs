@@ -0,0 +1,120 @@
+// Registry for series kernels that live in other extension modules. A plugin exports a
+// `FinaKernel` inside a PyCapsule named "fina.kernel" and hands it to `register_kernel`; the kernel
+// can then be run with `call_kernel` or used by name inside `compile_indicator` expressions.
+// Everything crosses a plain C ABI, so the plugin does not need to match fina's Rust or PyO3 version
+
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::sync::RwLock;
+
+use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
+
+use crate::errors::check_finite;
+use crate::input::{detach_large, floats, ArrayLike};
+use crate::with_slice;
+
+pub const ABI_VERSION: u32 = 1;
+const CAPSULE_NAME: &CStr = c"fina.kernel";
+
+// Writes len outputs for len inputs and returns 0, or a non-zero code that is reported as an error.
+// Called without the GIL and possibly from several threads at once
+pub type KernelFn = unsafe extern "C" fn(data: *const f64, len: usize, params: *const f64, out: *mut f64) -> i32;
+
+// Layout a plugin puts in its capsule
+#[repr(C)]
+pub struct FinaKernel {
+    pub abi_version: u32,
+    pub n_params: usize,
+    pub func: KernelFn,
+}
+
+#[derive(Clone, Copy)]
+pub struct Kernel {
+    pub n_params: usize,
+    func: KernelFn,
+}
+
+impl Kernel {
+    pub fn run(&self, name: &str, data: &[f64], params: &[f64]) -> Result<Vec<f64>, String> {
+        debug_assert_eq!(params.len(), self.n_params);
+        let mut out = vec![f64::NAN; data.len()];
+        // SAFETY: the plugin promised to read len values and n_params params and write len outputs
+        let code = unsafe { (self.func)(data.as_ptr(), data.len(), params.as_ptr(), out.as_mut_ptr()) };
+        if code != 0 {
+            return Err(format!("kernel {:?} failed with code {}", name, code));
+        }
+        Ok(out)
+    }
+}
+
+// The capsule is kept alongside the copied entry point so its owner stays referenced
+struct Entry {
+    kernel: Kernel,
+    _capsule: Py<PyCapsule>,
+}
+
+static REGISTRY: RwLock<Option<HashMap<String, Entry>>> = RwLock::new(None);
+
+pub fn lookup(name: &str) -> Option<Kernel> {
+    REGISTRY.read().unwrap().as_ref()?.get(name).map(|entry| entry.kernel)
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, capsule, replace=false))]
+pub fn register_kernel(name: &str, capsule: &Bound<'_, PyCapsule>, replace: bool) -> PyResult<()> {
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Kernel name must be an identifier"));
+    }
+    if crate::indicator::is_primitive(name) {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!("{:?} is a built-in indicator primitive", name)));
+    }
+    if !capsule.is_valid_checked(Some(CAPSULE_NAME)) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Capsule must be named \"fina.kernel\""));
+    }
+    // SAFETY: a capsule with this name holds a FinaKernel by contract
+    let spec = unsafe { &*(capsule.pointer_checked(Some(CAPSULE_NAME))?.as_ptr() as *const FinaKernel) };
+    if spec.abi_version != ABI_VERSION {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Kernel ABI version {} is not supported, expected {}",
+            spec.abi_version, ABI_VERSION
+        )));
+    }
+    let mut registry = REGISTRY.write().unwrap();
+    let kernels = registry.get_or_insert_with(HashMap::new);
+    if kernels.contains_key(name) && !replace {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "A kernel named {:?} is already registered; pass replace=True to override it",
+            name
+        )));
+    }
+    let kernel = Kernel { n_params: spec.n_params, func: spec.func };
+    kernels.insert(name.to_string(), Entry { kernel, _capsule: capsule.clone().unbind() });
+    Ok(())
+}
+
+// Registered kernel names, sorted
+#[pyfunction]
+pub fn list_kernels() -> Vec<String> {
+    let registry = REGISTRY.read().unwrap();
+    let mut names: Vec<String> = registry.as_ref().map(|k| k.keys().cloned().collect()).unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, data, *params))]
+pub fn call_kernel(py: Python<'_>, name: &str, data: ArrayLike, params: Vec<f64>) -> PyResult<Vec<f64>> {
+    let kernel = lookup(name).ok_or_else(|| pyo3::exceptions::PyValueError::new_err(format!("No kernel named {:?}", name)))?;
+    if params.len() != kernel.n_params {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Kernel {:?} takes {} parameters, got {}",
+            name,
+            kernel.n_params,
+            params.len()
+        )));
+    }
+    let data: Vec<f64> = with_slice!(data, values => floats(values).collect());
+    check_finite("call_kernel", "data", &data)?;
+    detach_large(py, data.len(), || kernel.run(name, &data, &params)).map_err(pyo3::exceptions::PyValueError::new_err)
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pyo3::prelude::*;
+
+// Unit-of-work hook for long native loops; errors abort the loop
+pub trait Step: Sync {
+    type Error;
+
+    fn step(&self) -> Result<(), Self::Error>;
+}
+
+// The loops run with the GIL released. Every `every` units of work this attaches briefly to run
+// Python's signal handlers, so Ctrl-C raises KeyboardInterrupt, and to pass (done, total) to the
+// optional callback
+pub struct Progress {
+    callback: Option<Py<PyAny>>,
+    every: usize,
+    total: usize,
+    done: AtomicUsize,
+}
+
+impl Progress {
+    pub fn new(callback: Option<Bound<'_, PyAny>>, every: usize, total: usize) -> PyResult<Self> {
+        if every == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("progress_every must be positive"));
+        }
+        if callback.as_ref().is_some_and(|cb| !cb.is_callable()) {
+            return Err(pyo3::exceptions::PyTypeError::new_err("progress must be callable"));
+        }
+        Ok(Progress { callback: callback.map(Bound::unbind), every, total, done: AtomicUsize::new(0) })
+    }
+}
+
+impl Step for Progress {
+    type Error = PyErr;
+
+    fn step(&self) -> PyResult<()> {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if done.is_multiple_of(self.every) || done == self.total {
+            Python::attach(|py| -> PyResult<()> {
+                py.check_signals()?;
+                if let Some(callback) = &self.callback {
+                    callback.bind(py).call1((done, self.total))?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+// Stands in for Progress where there is no interpreter, as in the unit tests
+#[cfg(test)]
+pub struct Silent;
+
+#[cfg(test)]
+impl Step for Silent {
+    type Error = std::convert::Infallible;
+
+    fn step(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
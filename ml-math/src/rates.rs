@@ -0,0 +1,132 @@
+use pyo3::prelude::*;
+
+use crate::curves::bracket;
+use crate::errors::Invalid;
+use crate::state::{persist_methods, persist_struct};
+
+// Simple forward rate between two discount factors
+#[pyfunction]
+pub fn fra_rate(df_start: f64, df_end: f64, year_frac: f64) -> PyResult<f64> {
+    if df_start <= 0.0 || df_end <= 0.0 || year_frac <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "Discount factors and year_frac must be positive",
+        ));
+    }
+    Ok((df_start / df_end - 1.0) / year_frac)
+}
+
+// Par rate of a spot-starting swap: the fixed leg annuity must match the floating leg, 1 - df(T_n)
+#[pyfunction]
+pub fn swap_rate(discount_factors: Vec<f64>, year_fracs: Vec<f64>) -> PyResult<f64> {
+    if discount_factors.is_empty() || discount_factors.len() != year_fracs.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "discount_factors and year_fracs must be non-empty with equal lengths",
+        ));
+    }
+    let annuity: f64 = discount_factors.iter().zip(year_fracs.iter()).map(|(df, tau)| df * tau).sum();
+    if annuity <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Annuity must be positive"));
+    }
+    Ok((1.0 - discount_factors[discount_factors.len() - 1]) / annuity)
+}
+
+// Value change for a one basis point parallel drop in continuously compounded zero rates
+#[pyfunction]
+pub fn dv01(cashflows: Vec<f64>, times: Vec<f64>, curve: Vec<f64>) -> PyResult<f64> {
+    if cashflows.len() != times.len() || times.len() != curve.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "cashflows, times and curve must have equal lengths",
+        ));
+    }
+    if let Some(i) = times.iter().position(|&t| !(t.is_finite() && t >= 0.0)) {
+        return Err(Invalid::at("times", i, times[i], "is not a non-negative time").raise("dv01"));
+    }
+    let pv = |shift: f64| -> f64 {
+        cashflows
+            .iter()
+            .zip(times.iter().zip(curve.iter()))
+            .map(|(cf, (t, r))| cf * (-(r + shift) * t).exp())
+            .sum()
+    };
+    Ok((pv(-1e-4) - pv(1e-4)) / 2.0)
+}
+
+#[pyclass(module = "fina")]
+pub struct DiscountCurve {
+    #[pyo3(get)]
+    tenors: Vec<f64>,
+    #[pyo3(get)]
+    zero_rates: Vec<f64>,
+}
+
+impl DiscountCurve {
+    // Log-linear discount factors between pillars, flat zero rates beyond the ends
+    fn zero_rate(&self, t: f64) -> f64 {
+        let n = self.tenors.len();
+        if t <= self.tenors[0] {
+            return self.zero_rates[0];
+        }
+        if t >= self.tenors[n - 1] {
+            return self.zero_rates[n - 1];
+        }
+        let (i, w) = bracket(&self.tenors, t);
+        let rt = (1.0 - w) * self.zero_rates[i] * self.tenors[i] + w * self.zero_rates[i + 1] * self.tenors[i + 1];
+        rt / t
+    }
+}
+
+persist_methods!(impl DiscountCurve {
+    #[new]
+    fn new(tenors: Vec<f64>, zero_rates: Vec<f64>) -> PyResult<Self> {
+        if tenors.is_empty() || tenors.len() != zero_rates.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "tenors and zero_rates must be non-empty with equal lengths",
+            ));
+        }
+        if tenors[0] <= 0.0 || tenors.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "tenors must be positive and strictly increasing",
+            ));
+        }
+        Ok(DiscountCurve { tenors, zero_rates })
+    }
+
+    fn df(&self, t: f64) -> PyResult<f64> {
+        if t < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("t cannot be negative"));
+        }
+        Ok((-self.zero_rate(t) * t).exp())
+    }
+
+    // Continuously compounded forward rate between t1 and t2
+    fn forward(&self, t1: f64, t2: f64) -> PyResult<f64> {
+        if t1 < 0.0 || t2 <= t1 {
+            return Err(pyo3::exceptions::PyValueError::new_err("Need 0 <= t1 < t2"));
+        }
+        Ok((self.zero_rate(t2) * t2 - self.zero_rate(t1) * t1) / (t2 - t1))
+    }
+
+    fn parallel_bump(&self, bps: f64) -> DiscountCurve {
+        DiscountCurve {
+            tenors: self.tenors.clone(),
+            zero_rates: self.zero_rates.iter().map(|r| r + bps * 1e-4).collect(),
+        }
+    }
+
+    // Bumps a single pillar; interpolation spreads it as a triangle to the neighbouring pillars
+    fn key_rate_bump(&self, tenor: f64, bps: f64) -> PyResult<DiscountCurve> {
+        let Some(k) = self.tenors.iter().position(|&t| (t - tenor).abs() < 1e-9) else {
+            return Err(pyo3::exceptions::PyValueError::new_err("tenor must be one of the curve pillars"));
+        };
+        let mut zero_rates = self.zero_rates.clone();
+        zero_rates[k] += bps * 1e-4;
+        Ok(DiscountCurve { tenors: self.tenors.clone(), zero_rates })
+    }
+
+    fn __repr__(&self) -> String {
+        let n = self.tenors.len();
+        format!("DiscountCurve(pillars={}, first={}, last={})", n, self.tenors[0], self.tenors[n - 1])
+    }
+});
+
+persist_struct!(DiscountCurve { tenors, zero_rates }, check = |c| !c.tenors.is_empty() && c.tenors.len() == c.zero_rates.len());
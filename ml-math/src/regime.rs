@@ -0,0 +1,464 @@
+use pyo3::prelude::*;
+
+use crate::errors::{check_finite, Invalid};
+use crate::progress::{Progress, Step};
+use crate::rng::Rng;
+use crate::state::{persist_methods, persist_struct};
+
+struct SegmentCost {
+    model: CostModel,
+    sum: Vec<f64>,
+    sum_sq: Vec<f64>,
+}
+
+#[derive(Clone, Copy)]
+enum CostModel {
+    Mean,
+    Variance,
+}
+
+impl SegmentCost {
+    fn new(data: &[f64], model: CostModel) -> Self {
+        let mut sum = Vec::with_capacity(data.len() + 1);
+        let mut sum_sq = Vec::with_capacity(data.len() + 1);
+        sum.push(0.0);
+        sum_sq.push(0.0);
+        for &x in data {
+            sum.push(sum[sum.len() - 1] + x);
+            sum_sq.push(sum_sq[sum_sq.len() - 1] + x * x);
+        }
+        SegmentCost { model, sum, sum_sq }
+    }
+
+    // Cost of the half-open segment data[start..end]
+    fn cost(&self, start: usize, end: usize) -> f64 {
+        let n = (end - start) as f64;
+        let s1 = self.sum[end] - self.sum[start];
+        let s2 = self.sum_sq[end] - self.sum_sq[start];
+        let rss = (s2 - s1 * s1 / n).max(0.0);
+        match self.model {
+            CostModel::Mean => rss,
+            CostModel::Variance => n * (rss / n).max(1e-12).ln(),
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, penalty, model="mean", min_size=2))]
+pub fn change_points(data: Vec<f64>, penalty: f64, model: &str, min_size: usize) -> PyResult<Vec<usize>> {
+    if data.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+    }
+    if penalty < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Penalty cannot be negative"));
+    }
+    if min_size == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("min_size must be at least 1"));
+    }
+    // One NaN would make every segment cost containing it NaN, and PELT would silently skip them
+    check_finite("change_points", "data", &data)?;
+    let model = match model {
+        "mean" => CostModel::Mean,
+        "variance" => CostModel::Variance,
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("model must be 'mean' or 'variance'")),
+    };
+
+    let n = data.len();
+    if n < 2 * min_size {
+        return Ok(Vec::new());
+    }
+    let cost = SegmentCost::new(&data, model);
+
+    // PELT: optimal partitioning with pruning of candidates that can never be optimal
+    let mut best = vec![f64::INFINITY; n + 1];
+    let mut last = vec![0usize; n + 1];
+    best[0] = -penalty;
+    let mut candidates: Vec<usize> = vec![0];
+
+    for t in min_size..=n {
+        let mut best_t = f64::INFINITY;
+        let mut arg = 0;
+        for &s in &candidates {
+            if t - s < min_size {
+                continue;
+            }
+            let value = best[s] + cost.cost(s, t) + penalty;
+            if value < best_t {
+                best_t = value;
+                arg = s;
+            }
+        }
+        best[t] = best_t;
+        last[t] = arg;
+
+        if best_t.is_finite() {
+            candidates.retain(|&s| t - s < min_size || best[s] + cost.cost(s, t) <= best_t);
+        }
+        if best_t.is_finite() && t + min_size <= n {
+            candidates.push(t);
+        }
+    }
+
+    let mut points = Vec::new();
+    let mut t = n;
+    while t > 0 {
+        let s = last[t];
+        if s > 0 {
+            points.push(s);
+        }
+        t = s;
+    }
+    points.reverse();
+    Ok(points)
+}
+
+#[pyclass(module = "fina")]
+#[derive(Clone)]
+pub struct GaussianHmm {
+    #[pyo3(get)]
+    start_probs: Vec<f64>,
+    #[pyo3(get)]
+    transition: Vec<Vec<f64>>,
+    #[pyo3(get)]
+    means: Vec<f64>,
+    #[pyo3(get)]
+    variances: Vec<f64>,
+    #[pyo3(get)]
+    log_likelihood: f64,
+    #[pyo3(get)]
+    n_iter: usize,
+}
+
+persist_methods!(impl GaussianHmm {
+    #[new]
+    fn new(start_probs: Vec<f64>, transition: Vec<Vec<f64>>, means: Vec<f64>, variances: Vec<f64>) -> PyResult<Self> {
+        let k = start_probs.len();
+        if k == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("Model must have at least one state"));
+        }
+        if transition.len() != k || transition.iter().any(|row| row.len() != k) || means.len() != k || variances.len() != k {
+            return Err(pyo3::exceptions::PyValueError::new_err("Model parameters must agree on the number of states"));
+        }
+        if let Some(i) = variances.iter().position(|&v| !(v.is_finite() && v > 0.0)) {
+            return Err(Invalid::at("variances", i, variances[i], "is not a positive variance").raise("GaussianHmm"));
+        }
+        Ok(GaussianHmm { start_probs, transition, means, variances, log_likelihood: f64::NAN, n_iter: 0 })
+    }
+
+    #[getter]
+    fn n_states(&self) -> usize {
+        self.means.len()
+    }
+});
+
+impl GaussianHmm {
+    fn emission(&self, state: usize, x: f64) -> f64 {
+        let var = self.variances[state];
+        let d = x - self.means[state];
+        libm::exp(-0.5 * d * d / var) / (2.0 * std::f64::consts::PI * var).sqrt()
+    }
+
+    fn log_emission(&self, state: usize, x: f64) -> f64 {
+        let var = self.variances[state];
+        let d = x - self.means[state];
+        -0.5 * d * d / var - 0.5 * libm::log(2.0 * std::f64::consts::PI * var)
+    }
+}
+
+// One Baum-Welch step with scaled forward-backward; returns the log-likelihood of the current model
+fn baum_welch_step(model: &mut GaussianHmm, data: &[f64]) -> f64 {
+    let k = model.means.len();
+    let n = data.len();
+    let mut emit = vec![0.0; n * k];
+    for (t, &x) in data.iter().enumerate() {
+        for i in 0..k {
+            emit[t * k + i] = model.emission(i, x).max(1e-300);
+        }
+    }
+
+    let mut alpha = vec![0.0; n * k];
+    let mut scale = vec![0.0; n];
+    for i in 0..k {
+        alpha[i] = model.start_probs[i] * emit[i];
+    }
+    scale[0] = alpha[..k].iter().sum::<f64>().max(1e-300);
+    for a in &mut alpha[..k] {
+        *a /= scale[0];
+    }
+    for t in 1..n {
+        for j in 0..k {
+            let mut acc = 0.0;
+            for i in 0..k {
+                acc += alpha[(t - 1) * k + i] * model.transition[i][j];
+            }
+            alpha[t * k + j] = acc * emit[t * k + j];
+        }
+        scale[t] = alpha[t * k..(t + 1) * k].iter().sum::<f64>().max(1e-300);
+        for a in &mut alpha[t * k..(t + 1) * k] {
+            *a /= scale[t];
+        }
+    }
+
+    let mut beta = vec![1.0; n * k];
+    for t in (0..n - 1).rev() {
+        for i in 0..k {
+            let mut acc = 0.0;
+            for j in 0..k {
+                acc += model.transition[i][j] * emit[(t + 1) * k + j] * beta[(t + 1) * k + j];
+            }
+            beta[t * k + i] = acc / scale[t + 1];
+        }
+    }
+
+    let mut gamma = vec![0.0; n * k];
+    for t in 0..n {
+        let norm: f64 = (0..k).map(|i| alpha[t * k + i] * beta[t * k + i]).sum::<f64>().max(1e-300);
+        for i in 0..k {
+            gamma[t * k + i] = alpha[t * k + i] * beta[t * k + i] / norm;
+        }
+    }
+
+    let mut xi_sum = vec![vec![0.0; k]; k];
+    for t in 0..n - 1 {
+        let mut norm = 0.0;
+        for i in 0..k {
+            for j in 0..k {
+                norm += alpha[t * k + i] * model.transition[i][j] * emit[(t + 1) * k + j] * beta[(t + 1) * k + j];
+            }
+        }
+        let norm = norm.max(1e-300);
+        for i in 0..k {
+            for j in 0..k {
+                xi_sum[i][j] += alpha[t * k + i] * model.transition[i][j] * emit[(t + 1) * k + j] * beta[(t + 1) * k + j] / norm;
+            }
+        }
+    }
+
+    let data_var = {
+        let m = data.iter().sum::<f64>() / n as f64;
+        data.iter().map(|x| (x - m).powi(2)).sum::<f64>() / n as f64
+    };
+    let var_floor = (data_var * 1e-6).max(1e-12);
+
+    for i in 0..k {
+        model.start_probs[i] = gamma[i];
+        let row_total: f64 = xi_sum[i].iter().sum();
+        if row_total > 0.0 {
+            for (p, &x) in model.transition[i].iter_mut().zip(&xi_sum[i]) {
+                *p = x / row_total;
+            }
+        }
+        let weight: f64 = (0..n).map(|t| gamma[t * k + i]).sum();
+        if weight > 1e-12 {
+            let mu = (0..n).map(|t| gamma[t * k + i] * data[t]).sum::<f64>() / weight;
+            let var = (0..n).map(|t| gamma[t * k + i] * (data[t] - mu).powi(2)).sum::<f64>() / weight;
+            model.means[i] = mu;
+            model.variances[i] = var.max(var_floor);
+        }
+    }
+
+    scale.iter().map(|&c| libm::log(c)).sum()
+}
+
+// `progress(done, max_iter)` is called every `progress_every` EM iterations
+#[pyfunction]
+#[pyo3(signature = (returns, n_states, max_iter=100, seed=None, tol=1e-6, progress=None, progress_every=1))]
+#[allow(clippy::too_many_arguments)]
+pub fn hmm_fit<'py>(
+    py: Python<'py>,
+    returns: Vec<f64>,
+    n_states: usize,
+    max_iter: usize,
+    seed: Option<u64>,
+    tol: f64,
+    progress: Option<Bound<'py, PyAny>>,
+    progress_every: usize,
+) -> PyResult<GaussianHmm> {
+    if n_states == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_states must be at least 1"));
+    }
+    if returns.len() < 2 * n_states {
+        return Err(pyo3::exceptions::PyValueError::new_err("Need at least two observations per state"));
+    }
+    check_finite("hmm_fit", "returns", &returns)?;
+
+    let progress = Progress::new(progress, progress_every, max_iter)?;
+    let mut rng = Rng::from_seed(seed);
+    py.detach(|| fit_hmm(&returns, n_states, max_iter, tol, &mut rng, &progress))
+}
+
+fn fit_hmm<P: Step>(returns: &[f64], n_states: usize, max_iter: usize, tol: f64, rng: &mut Rng, progress: &P) -> Result<GaussianHmm, P::Error> {
+    let n = returns.len();
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let m = returns.iter().sum::<f64>() / n as f64;
+    let var = (returns.iter().map(|x| (x - m).powi(2)).sum::<f64>() / n as f64).max(1e-12);
+
+    // Spread initial means over the quantiles with a small random jitter
+    let means: Vec<f64> = (0..n_states)
+        .map(|i| {
+            let idx = ((i as f64 + 0.5) / n_states as f64 * n as f64) as usize;
+            sorted[idx.min(n - 1)] + 0.1 * var.sqrt() * rng.normal()
+        })
+        .collect();
+    let transition: Vec<Vec<f64>> = (0..n_states)
+        .map(|i| {
+            let mut row: Vec<f64> = (0..n_states)
+                .map(|j| if i == j { 0.9 } else { 0.1 / (n_states as f64).max(2.0) } + 0.01 * rng.next_f64())
+                .collect();
+            let total: f64 = row.iter().sum();
+            row.iter_mut().for_each(|p| *p /= total);
+            row
+        })
+        .collect();
+
+    let mut model = GaussianHmm {
+        start_probs: vec![1.0 / n_states as f64; n_states],
+        transition,
+        means,
+        variances: vec![var; n_states],
+        log_likelihood: f64::NEG_INFINITY,
+        n_iter: 0,
+    };
+
+    let mut prev = f64::NEG_INFINITY;
+    for iter in 0..max_iter {
+        let ll = baum_welch_step(&mut model, returns);
+        model.log_likelihood = ll;
+        model.n_iter = iter + 1;
+        progress.step()?;
+        if (ll - prev).abs() < tol {
+            break;
+        }
+        prev = ll;
+    }
+    Ok(model)
+}
+
+#[pyfunction]
+pub fn hmm_viterbi(model: PyRef<GaussianHmm>, returns: Vec<f64>) -> PyResult<Vec<usize>> {
+    if returns.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Returns cannot be empty"));
+    }
+    let k = model.means.len();
+    let n = returns.len();
+    let log_trans: Vec<Vec<f64>> = model.transition.iter().map(|row| row.iter().map(|p| libm::log(p.max(1e-300))).collect()).collect();
+
+    let mut delta: Vec<f64> = (0..k).map(|i| libm::log(model.start_probs[i].max(1e-300)) + model.log_emission(i, returns[0])).collect();
+    let mut backptr = vec![0usize; n * k];
+    for t in 1..n {
+        let mut next = vec![f64::NEG_INFINITY; k];
+        for j in 0..k {
+            for i in 0..k {
+                let score = delta[i] + log_trans[i][j];
+                if score > next[j] {
+                    next[j] = score;
+                    backptr[t * k + j] = i;
+                }
+            }
+            next[j] += model.log_emission(j, returns[t]);
+        }
+        delta = next;
+    }
+
+    let mut state = (0..k).fold(0, |best, i| if delta[i] > delta[best] { i } else { best });
+    let mut path = vec![0usize; n];
+    for t in (0..n).rev() {
+        path[t] = state;
+        if t > 0 {
+            state = backptr[t * k + state];
+        }
+    }
+    Ok(path)
+}
+
+#[pyfunction]
+pub fn transition_matrix(state_sequence: Vec<usize>, n_states: usize) -> PyResult<Vec<Vec<f64>>> {
+    if n_states == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("n_states must be at least 1"));
+    }
+    if let Some(&bad) = state_sequence.iter().find(|&&s| s >= n_states) {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!("State {} is out of range for {} states", bad, n_states)));
+    }
+
+    let mut counts = vec![vec![0.0; n_states]; n_states];
+    for pair in state_sequence.windows(2) {
+        counts[pair[0]][pair[1]] += 1.0;
+    }
+    // Rows for states that never transition stay all zero
+    for row in counts.iter_mut() {
+        let total: f64 = row.iter().sum();
+        if total > 0.0 {
+            row.iter_mut().for_each(|c| *c /= total);
+        }
+    }
+    Ok(counts)
+}
+
+#[pyfunction]
+#[pyo3(signature = (transition_matrix, n_steps, seed=None, initial_state=0))]
+pub fn simulate_markov(transition_matrix: Vec<Vec<f64>>, n_steps: usize, seed: Option<u64>, initial_state: usize) -> PyResult<Vec<usize>> {
+    let k = transition_matrix.len();
+    if k == 0 || transition_matrix.iter().any(|row| row.len() != k) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Transition matrix must be square and non-empty"));
+    }
+    for (r, row) in transition_matrix.iter().enumerate() {
+        if let Some(j) = row.iter().position(|&p| !(p.is_finite() && p >= 0.0)) {
+            let reason = format!("is not a valid probability (row {}, column {})", r, j);
+            return Err(Invalid::at("transition_matrix", r, row[j], reason).raise("simulate_markov"));
+        }
+        let total: f64 = row.iter().sum();
+        if (total - 1.0).abs() > 1e-8 {
+            return Err(Invalid::at("transition_matrix", r, total, "is the row sum, which must be 1").raise("simulate_markov"));
+        }
+    }
+    if initial_state >= k {
+        return Err(pyo3::exceptions::PyValueError::new_err("initial_state is out of range"));
+    }
+
+    let mut rng = Rng::from_seed(seed);
+    let mut path = Vec::with_capacity(n_steps);
+    let mut state = initial_state;
+    for _ in 0..n_steps {
+        path.push(state);
+        let u = rng.next_f64();
+        let mut cumulative = 0.0;
+        let mut next = k - 1;
+        for (j, &p) in transition_matrix[state].iter().enumerate() {
+            cumulative += p;
+            if u < cumulative {
+                next = j;
+                break;
+            }
+        }
+        state = next;
+    }
+    Ok(path)
+}
+
+persist_struct!(
+    GaussianHmm { start_probs, transition, means, variances, log_likelihood, n_iter },
+    check = |h| {
+        let k = h.start_probs.len();
+        k > 0 && h.transition.len() == k && h.transition.iter().all(|row| row.len() == k) && h.means.len() == k && h.variances.len() == k
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::Silent;
+
+    #[test]
+    fn fit_hmm_is_pinned_for_a_seed() {
+        // Alternating calm and volatile blocks of 50 observations
+        let mut rng = Rng::new(3);
+        let returns: Vec<f64> = (0..300)
+            .map(|i| if (i / 50) % 2 == 0 { 0.002 + 0.005 * rng.normal() } else { -0.003 + 0.02 * rng.normal() })
+            .collect();
+        let Ok(hmm) = fit_hmm(&returns, 2, 50, 1e-8, &mut Rng::new(9), &Silent);
+        assert_eq!(hmm.means, [-0.0020255268677839043, 0.0019655474204968333]);
+        assert_eq!(hmm.variances, [0.00046114902004534364, 2.8844753622998323e-5]);
+        assert_eq!(hmm.log_likelihood, 913.2117295292728);
+    }
+}
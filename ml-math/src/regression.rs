@@ -0,0 +1,406 @@
+use pyo3::prelude::*;
+
+use crate::errors::check_finite;
+use crate::linalg::solve_dense;
+
+// Shared shape checks for (X rows, y) inputs; returns the number of features
+fn validate_xy(x: &[Vec<f64>], y: &[f64]) -> PyResult<usize> {
+    if x.is_empty() || x.len() != y.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("X and y must be non-empty with the same number of rows"));
+    }
+    let p = x[0].len();
+    if p == 0 || x.iter().any(|row| row.len() != p) {
+        return Err(pyo3::exceptions::PyValueError::new_err("X rows must be non-empty and equal length"));
+    }
+    Ok(p)
+}
+
+// Design matrix with a leading column of ones when fitting an intercept
+fn design(x: &[Vec<f64>], fit_intercept: bool) -> Vec<Vec<f64>> {
+    x.iter()
+        .map(|row| if fit_intercept { std::iter::once(1.0).chain(row.iter().copied()).collect() } else { row.clone() })
+        .collect()
+}
+
+// Solves (A' W A + diag(penalty)) beta = A' W y
+fn weighted_normal_equations(a: &[Vec<f64>], y: &[f64], w: &[f64], penalty: &[f64]) -> Option<Vec<f64>> {
+    let k = a[0].len();
+    let mut ata = vec![vec![0.0; k]; k];
+    let mut aty = vec![0.0; k];
+    for ((row, &yi), &wi) in a.iter().zip(y).zip(w) {
+        for ((ata_row, aty_i), &xi) in ata.iter_mut().zip(aty.iter_mut()).zip(row) {
+            *aty_i += wi * xi * yi;
+            for (cell, &xj) in ata_row.iter_mut().zip(row) {
+                *cell += wi * xi * xj;
+            }
+        }
+    }
+    for (i, (ata_row, &pen)) in ata.iter_mut().zip(penalty).enumerate() {
+        ata_row[i] += pen;
+    }
+    solve_dense(ata, aty)
+}
+
+fn split_intercept(mut beta: Vec<f64>, fit_intercept: bool) -> (Vec<f64>, f64) {
+    if fit_intercept {
+        let intercept = beta.remove(0);
+        (beta, intercept)
+    } else {
+        (beta, 0.0)
+    }
+}
+
+// Linear quantile regression by iteratively reweighted least squares on the check loss;
+// returns (coefficients, intercept)
+#[pyfunction]
+#[pyo3(signature = (x_2d, y, tau=0.5, max_iter=100, tol=1e-8, fit_intercept=true))]
+pub fn quantile_regression(
+    x_2d: Vec<Vec<f64>>,
+    y: Vec<f64>,
+    tau: f64,
+    max_iter: usize,
+    tol: f64,
+    fit_intercept: bool,
+) -> PyResult<(Vec<f64>, f64)> {
+    validate_xy(&x_2d, &y)?;
+    if !(tau > 0.0 && tau < 1.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("tau must be in (0, 1)"));
+    }
+    let a = design(&x_2d, fit_intercept);
+    let k = a[0].len();
+    let singular = || pyo3::exceptions::PyValueError::new_err("Design matrix is singular");
+    let mut weights = vec![1.0; y.len()];
+    let mut beta = weighted_normal_equations(&a, &y, &weights, &vec![0.0; k]).ok_or_else(singular)?;
+
+    // Residuals are floored so observations sitting on the fit do not get infinite weight
+    let scale = y.iter().map(|v| v.abs()).fold(0.0, f64::max).max(1.0);
+    let floor = 1e-8 * scale;
+    for _ in 0..max_iter {
+        for ((w, row), &yi) in weights.iter_mut().zip(&a).zip(&y) {
+            let r = yi - row.iter().zip(&beta).map(|(x, b)| x * b).sum::<f64>();
+            let side = if r >= 0.0 { tau } else { 1.0 - tau };
+            *w = side / r.abs().max(floor);
+        }
+        let next = weighted_normal_equations(&a, &y, &weights, &vec![0.0; k]).ok_or_else(singular)?;
+        let change = next.iter().zip(&beta).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max);
+        beta = next;
+        if change < tol {
+            break;
+        }
+    }
+    Ok(split_intercept(beta, fit_intercept))
+}
+
+// Centered feature columns, centered target and the means needed to recover an intercept
+fn centered_columns(x: &[Vec<f64>], y: &[f64], fit_intercept: bool) -> (Vec<Vec<f64>>, Vec<f64>, Vec<f64>, f64) {
+    let n = x.len() as f64;
+    let p = x[0].len();
+    let mut columns: Vec<Vec<f64>> = (0..p).map(|j| x.iter().map(|row| row[j]).collect()).collect();
+    let mut means = vec![0.0; p];
+    let mut y_mean = 0.0;
+    let mut yc = y.to_vec();
+    if fit_intercept {
+        for (col, mean) in columns.iter_mut().zip(means.iter_mut()) {
+            *mean = col.iter().sum::<f64>() / n;
+            col.iter_mut().for_each(|v| *v -= *mean);
+        }
+        y_mean = y.iter().sum::<f64>() / n;
+        yc.iter_mut().for_each(|v| *v -= y_mean);
+    }
+    (columns, yc, means, y_mean)
+}
+
+fn intercept_from_means(coef: &[f64], means: &[f64], y_mean: f64) -> f64 {
+    y_mean - coef.iter().zip(means).map(|(b, m)| b * m).sum::<f64>()
+}
+
+// Minimizes ||y - Xb||^2 + alpha ||b||^2; the intercept is not penalized
+#[pyfunction]
+#[pyo3(signature = (x, y, alpha=1.0, fit_intercept=true))]
+pub fn ridge(x: Vec<Vec<f64>>, y: Vec<f64>, alpha: f64, fit_intercept: bool) -> PyResult<(Vec<f64>, f64)> {
+    let p = validate_xy(&x, &y)?;
+    if alpha < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("alpha cannot be negative"));
+    }
+    let (columns, yc, means, y_mean) = centered_columns(&x, &y, fit_intercept);
+    let rows: Vec<Vec<f64>> = (0..x.len()).map(|i| columns.iter().map(|c| c[i]).collect()).collect();
+    let coef = weighted_normal_equations(&rows, &yc, &vec![1.0; y.len()], &vec![alpha; p])
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("System is singular; increase alpha"))?;
+    let intercept = if fit_intercept { intercept_from_means(&coef, &means, y_mean) } else { 0.0 };
+    Ok((coef, intercept))
+}
+
+// Cyclic coordinate descent for (1 / 2n) ||y - Xb||^2 + alpha * l1_ratio * ||b||_1
+// + alpha * (1 - l1_ratio) / 2 * ||b||^2, matching scikit-learn's parameterization
+fn coordinate_descent(
+    x: &[Vec<f64>],
+    y: &[f64],
+    alpha: f64,
+    l1_ratio: f64,
+    max_iter: usize,
+    tol: f64,
+    fit_intercept: bool,
+) -> PyResult<(Vec<f64>, f64)> {
+    let p = validate_xy(x, y)?;
+    if alpha < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("alpha cannot be negative"));
+    }
+    if !(0.0..=1.0).contains(&l1_ratio) {
+        return Err(pyo3::exceptions::PyValueError::new_err("l1_ratio must be in [0, 1]"));
+    }
+    let n = x.len() as f64;
+    let (columns, yc, means, y_mean) = centered_columns(x, y, fit_intercept);
+    let norms: Vec<f64> = columns.iter().map(|c| c.iter().map(|v| v * v).sum::<f64>() / n).collect();
+    let l1 = alpha * l1_ratio;
+    let l2 = alpha * (1.0 - l1_ratio);
+    let mut coef = vec![0.0; p];
+    let mut residual = yc;
+    for _ in 0..max_iter {
+        let mut max_change: f64 = 0.0;
+        for j in 0..p {
+            let denom = norms[j] + l2;
+            if denom == 0.0 {
+                continue;
+            }
+            let old = coef[j];
+            let rho = columns[j].iter().zip(&residual).map(|(xj, r)| xj * r).sum::<f64>() / n + norms[j] * old;
+            let new = rho.signum() * (rho.abs() - l1).max(0.0) / denom;
+            if new != old {
+                let delta = new - old;
+                residual.iter_mut().zip(&columns[j]).for_each(|(r, xj)| *r -= delta * xj);
+                coef[j] = new;
+                max_change = max_change.max(delta.abs());
+            }
+        }
+        if max_change < tol {
+            break;
+        }
+    }
+    let intercept = if fit_intercept { intercept_from_means(&coef, &means, y_mean) } else { 0.0 };
+    Ok((coef, intercept))
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, alpha=1.0, max_iter=1000, tol=1e-6, fit_intercept=true))]
+pub fn lasso(
+    x: Vec<Vec<f64>>,
+    y: Vec<f64>,
+    alpha: f64,
+    max_iter: usize,
+    tol: f64,
+    fit_intercept: bool,
+) -> PyResult<(Vec<f64>, f64)> {
+    coordinate_descent(&x, &y, alpha, 1.0, max_iter, tol, fit_intercept)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, alpha=1.0, l1_ratio=0.5, max_iter=1000, tol=1e-6, fit_intercept=true))]
+pub fn elastic_net(
+    x: Vec<Vec<f64>>,
+    y: Vec<f64>,
+    alpha: f64,
+    l1_ratio: f64,
+    max_iter: usize,
+    tol: f64,
+    fit_intercept: bool,
+) -> PyResult<(Vec<f64>, f64)> {
+    coordinate_descent(&x, &y, alpha, l1_ratio, max_iter, tol, fit_intercept)
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let n = values.len();
+    if n % 2 == 1 { values[n / 2] } else { 0.5 * (values[n / 2 - 1] + values[n / 2]) }
+}
+
+// Huber M-estimator by iteratively reweighted least squares; residuals beyond epsilon robust
+// standard deviations (MAD / 0.6745) are down-weighted. Returns (coefficients, intercept)
+#[pyfunction]
+#[pyo3(signature = (x, y, epsilon=1.35, max_iter=100, tol=1e-8, fit_intercept=true))]
+pub fn huber_regression(
+    x: Vec<Vec<f64>>,
+    y: Vec<f64>,
+    epsilon: f64,
+    max_iter: usize,
+    tol: f64,
+    fit_intercept: bool,
+) -> PyResult<(Vec<f64>, f64)> {
+    validate_xy(&x, &y)?;
+    if !epsilon.is_finite() || epsilon < 1.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("epsilon must be at least 1.0"));
+    }
+    let a = design(&x, fit_intercept);
+    let k = a[0].len();
+    let singular = || pyo3::exceptions::PyValueError::new_err("Design matrix is singular");
+    let mut weights = vec![1.0; y.len()];
+    let mut beta = weighted_normal_equations(&a, &y, &weights, &vec![0.0; k]).ok_or_else(singular)?;
+
+    for _ in 0..max_iter {
+        let residuals: Vec<f64> =
+            a.iter().zip(&y).map(|(row, &yi)| yi - row.iter().zip(&beta).map(|(x, b)| x * b).sum::<f64>()).collect();
+        let mut abs: Vec<f64> = residuals.iter().map(|r| r.abs()).collect();
+        let scale = median(&mut abs) / 0.6745;
+        if scale <= f64::EPSILON {
+            break;
+        }
+        let cutoff = epsilon * scale;
+        for (w, r) in weights.iter_mut().zip(&residuals) {
+            *w = if r.abs() <= cutoff { 1.0 } else { cutoff / r.abs() };
+        }
+        let next = weighted_normal_equations(&a, &y, &weights, &vec![0.0; k]).ok_or_else(singular)?;
+        let change = next.iter().zip(&beta).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max);
+        beta = next;
+        if change < tol {
+            break;
+        }
+    }
+    Ok(split_intercept(beta, fit_intercept))
+}
+
+// Median of pairwise slopes, intercept as the median of y - slope * x; returns (slope, intercept)
+#[pyfunction]
+pub fn theil_sen(x: Vec<f64>, y: Vec<f64>) -> PyResult<(f64, f64)> {
+    if x.len() != y.len() || x.len() < 2 {
+        return Err(pyo3::exceptions::PyValueError::new_err("x and y must have equal length of at least 2"));
+    }
+    let mut slopes = Vec::with_capacity(x.len() * (x.len() - 1) / 2);
+    for i in 0..x.len() {
+        for j in i + 1..x.len() {
+            let dx = x[j] - x[i];
+            if dx != 0.0 {
+                slopes.push((y[j] - y[i]) / dx);
+            }
+        }
+    }
+    if slopes.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("x must contain at least two distinct values"));
+    }
+    let slope = median(&mut slopes);
+    let mut offsets: Vec<f64> = x.iter().zip(&y).map(|(xi, yi)| yi - slope * xi).collect();
+    Ok((slope, median(&mut offsets)))
+}
+
+// Pool-adjacent-violators on targets ordered by score, tied scores pooled up front;
+// returns the step function as (distinct scores, non-decreasing fitted values)
+#[pyfunction]
+#[pyo3(signature = (scores, targets, weights=None))]
+pub fn isotonic_fit(scores: Vec<f64>, targets: Vec<f64>, weights: Option<Vec<f64>>) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    if scores.is_empty() || scores.len() != targets.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("scores and targets must be non-empty and same length"));
+    }
+    let weights = weights.unwrap_or_else(|| vec![1.0; scores.len()]);
+    if weights.len() != scores.len() || weights.iter().any(|w| !w.is_finite() || *w <= 0.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("weights must be positive and match scores"));
+    }
+    check_finite("isotonic_fit", "scores", &scores)?;
+    check_finite("isotonic_fit", "targets", &targets)?;
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[a].total_cmp(&scores[b]));
+
+    // Tied scores collapse into one weighted observation before pooling
+    let mut knots: Vec<f64> = Vec::new();
+    let mut points: Vec<(f64, f64)> = Vec::new();
+    for &i in &order {
+        if knots.last() == Some(&scores[i]) {
+            let last = points.last_mut().unwrap();
+            let w = last.1 + weights[i];
+            last.0 += (targets[i] - last.0) * weights[i] / w;
+            last.1 = w;
+        } else {
+            knots.push(scores[i]);
+            points.push((targets[i], weights[i]));
+        }
+    }
+
+    // Blocks hold (weighted mean, total weight, number of distinct scores pooled)
+    let mut blocks: Vec<(f64, f64, usize)> = Vec::with_capacity(points.len());
+    for (mean, weight) in points {
+        blocks.push((mean, weight, 1));
+        while blocks.len() > 1 && blocks[blocks.len() - 2].0 > blocks[blocks.len() - 1].0 {
+            let (m2, w2, c2) = blocks.pop().unwrap();
+            let last = blocks.last_mut().unwrap();
+            let w = last.1 + w2;
+            last.0 = (last.0 * last.1 + m2 * w2) / w;
+            last.1 = w;
+            last.2 += c2;
+        }
+    }
+    let fitted = blocks.iter().flat_map(|&(m, _, c)| std::iter::repeat_n(m, c)).collect();
+    Ok((knots, fitted))
+}
+
+// Platt's sigmoid P(y=1 | s) = 1 / (1 + exp(a * s + b)) fitted by Newton's method with
+// backtracking on the smoothed targets of Lin, Lin & Weng (2007); returns (a, b)
+#[pyfunction]
+#[pyo3(signature = (scores, targets, max_iter=100, tol=1e-10))]
+pub fn platt_scaling(scores: Vec<f64>, targets: Vec<u8>, max_iter: usize, tol: f64) -> PyResult<(f64, f64)> {
+    if scores.is_empty() || scores.len() != targets.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("scores and targets must be non-empty and same length"));
+    }
+    if targets.iter().any(|&t| t > 1) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Targets must be 0 or 1"));
+    }
+    check_finite("platt_scaling", "scores", &scores)?;
+    let n_pos = targets.iter().filter(|&&t| t == 1).count() as f64;
+    let n_neg = targets.len() as f64 - n_pos;
+    let hi = (n_pos + 1.0) / (n_pos + 2.0);
+    let lo = 1.0 / (n_neg + 2.0);
+    let t: Vec<f64> = targets.iter().map(|&v| if v == 1 { hi } else { lo }).collect();
+
+    // Cross-entropy of the sigmoid written so that exp never overflows
+    let loss = |a: f64, b: f64| -> f64 {
+        scores
+            .iter()
+            .zip(&t)
+            .map(|(s, ti)| {
+                let f = a * s + b;
+                if f >= 0.0 { ti * f + (1.0 + (-f).exp()).ln() } else { (ti - 1.0) * f + (1.0 + f.exp()).ln() }
+            })
+            .sum()
+    };
+    let mut a = 0.0;
+    let mut b = ((n_neg + 1.0) / (n_pos + 1.0)).ln();
+    let mut current = loss(a, b);
+    for _ in 0..max_iter {
+        let (mut h11, mut h22, mut h21, mut g1, mut g2) = (1e-12, 1e-12, 0.0, 0.0, 0.0);
+        for (s, ti) in scores.iter().zip(&t) {
+            let f = a * s + b;
+            let (p, q) = if f >= 0.0 {
+                let e = (-f).exp();
+                (e / (1.0 + e), 1.0 / (1.0 + e))
+            } else {
+                let e = f.exp();
+                (1.0 / (1.0 + e), e / (1.0 + e))
+            };
+            let d2 = p * q;
+            h11 += s * s * d2;
+            h22 += d2;
+            h21 += s * d2;
+            let d1 = ti - p;
+            g1 += s * d1;
+            g2 += d1;
+        }
+        if g1.abs() < tol && g2.abs() < tol {
+            break;
+        }
+        let det = h11 * h22 - h21 * h21;
+        let da = -(h22 * g1 - h21 * g2) / det;
+        let db = -(-h21 * g1 + h11 * g2) / det;
+        let gd = g1 * da + g2 * db;
+        let mut step = 1.0;
+        while step >= 1e-10 {
+            let candidate = loss(a + step * da, b + step * db);
+            if candidate < current + 1e-4 * step * gd {
+                a += step * da;
+                b += step * db;
+                current = candidate;
+                break;
+            }
+            step /= 2.0;
+        }
+        if step < 1e-10 {
+            break;
+        }
+    }
+    Ok((a, b))
+}
@@ -0,0 +1,155 @@
+// Sliding-window statistics, O(n) except rolling_apply: output i covers data[i..i + window], so
+// a series of length n gives n - window + 1 values
+
+use std::collections::VecDeque;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::errors::{all_finite, Invalid};
+use crate::input::{detach_large, floats, ArrayLike, Element};
+use crate::with_slice;
+
+fn check_size(len: usize, window: usize) -> Result<(), Invalid> {
+    if window == 0 {
+        return Err(Invalid::new("window", "must be positive"));
+    }
+    if len < window {
+        return Err(Invalid::new("data", format!("has {} elements, fewer than the window of {}", len, window)));
+    }
+    Ok(())
+}
+
+fn check_window<T: Element>(data: &[T], window: usize) -> Result<(), Invalid> {
+    check_size(data.len(), window)?;
+    all_finite("data", data)
+}
+
+// Running sum with Neumaier compensation, so adding and dropping values does not drift
+#[derive(Default)]
+struct CompensatedSum {
+    sum: f64,
+    carry: f64,
+}
+
+impl CompensatedSum {
+    fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        self.carry += if self.sum.abs() >= x.abs() { (self.sum - t) + x } else { (x - t) + self.sum };
+        self.sum = t;
+    }
+
+    fn value(&self) -> f64 {
+        self.sum + self.carry
+    }
+}
+
+pub fn window_sums<T: Element>(data: &[T], window: usize) -> Vec<f64> {
+    let mut sum = CompensatedSum::default();
+    floats(&data[..window]).for_each(|x| sum.add(x));
+    let mut out = Vec::with_capacity(data.len() - window + 1);
+    out.push(sum.value());
+    for (new, old) in floats(&data[window..]).zip(floats(data)) {
+        sum.add(new);
+        sum.add(-old);
+        out.push(sum.value());
+    }
+    out
+}
+
+// Monotonic deque of indices whose values are kept in `keep` order; the front is the extreme
+pub fn window_extreme<T: Element>(data: &[T], window: usize, keep: impl Fn(f64, f64) -> bool) -> Vec<f64> {
+    let mut deque: VecDeque<usize> = VecDeque::with_capacity(window);
+    let mut out = Vec::with_capacity(data.len() - window + 1);
+    for (i, x) in floats(data).enumerate() {
+        while deque.back().is_some_and(|&j| !keep(data[j].to_f64(), x)) {
+            deque.pop_back();
+        }
+        deque.push_back(i);
+        if deque[0] + window <= i {
+            deque.pop_front();
+        }
+        if i + 1 >= window {
+            out.push(data[deque[0]].to_f64());
+        }
+    }
+    out
+}
+
+#[pyfunction]
+pub fn rolling_sum(py: Python<'_>, data: ArrayLike, window: usize) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || check_window(data, window).map(|_| window_sums(data, window))))
+        .map_err(|e| e.raise("rolling_sum"))
+}
+
+#[pyfunction]
+pub fn rolling_mean(py: Python<'_>, data: ArrayLike, window: usize) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || {
+        check_window(data, window).map(|_| window_sums(data, window).into_iter().map(|s| s / window as f64).collect())
+    }))
+    .map_err(|e| e.raise("rolling_mean"))
+}
+
+// Sliding Welford update: swapping x_old for x_new moves the mean by (x_new - x_old) / w and
+// the squared-deviation sum by (x_new - x_old)(x_new - mean_new + x_old - mean_old).
+// ddof=0 is the population std used by std_dev, ddof=1 the sample std pandas defaults to
+#[pyfunction]
+#[pyo3(signature = (data, window, ddof=0))]
+pub fn rolling_std(py: Python<'_>, data: ArrayLike, window: usize, ddof: usize) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || {
+        check_window(data, window)?;
+        if ddof >= window {
+            return Err(Invalid::new("ddof", "must be less than window"));
+        }
+        Ok(window_stds(data, window, ddof))
+    }))
+    .map_err(|e| e.raise("rolling_std"))
+}
+
+pub fn window_stds<T: Element>(data: &[T], window: usize, ddof: usize) -> Vec<f64> {
+    let w = window as f64;
+    let mut mean = floats(&data[..window]).sum::<f64>() / w;
+    let mut m2: f64 = floats(&data[..window]).map(|x| (x - mean).powi(2)).sum();
+    let denom = (window - ddof) as f64;
+    let mut out = Vec::with_capacity(data.len() - window + 1);
+    out.push((m2 / denom).sqrt());
+    for (new, old) in floats(&data[window..]).zip(floats(data)) {
+        let next_mean = mean + (new - old) / w;
+        m2 += (new - old) * (new - next_mean + old - mean);
+        mean = next_mean;
+        out.push((m2.max(0.0) / denom).sqrt());
+    }
+    out
+}
+
+#[pyfunction]
+pub fn rolling_min(py: Python<'_>, data: ArrayLike, window: usize) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || {
+        check_window(data, window).map(|_| window_extreme(data, window, |kept, x| kept < x))
+    }))
+    .map_err(|e| e.raise("rolling_min"))
+}
+
+#[pyfunction]
+pub fn rolling_max(py: Python<'_>, data: ArrayLike, window: usize) -> PyResult<Vec<f64>> {
+    with_slice!(data, data => detach_large(py, data.len(), || {
+        check_window(data, window).map(|_| window_extreme(data, window, |kept, x| kept > x))
+    }))
+    .map_err(|e| e.raise("rolling_max"))
+}
+
+// Calls func(window) on each window, passed as a list of floats, and collects the float
+// results. The callable needs the GIL, so unlike the built-in rolling functions this holds
+// it throughout; NaN in data is passed through for func to handle
+#[pyfunction]
+pub fn rolling_apply(py: Python<'_>, data: ArrayLike, window: usize, func: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    if !func.is_callable() {
+        return Err(pyo3::exceptions::PyTypeError::new_err("func must be callable"));
+    }
+    with_slice!(data, data => {
+        check_size(data.len(), window).map_err(|e| e.raise("rolling_apply"))?;
+        data.windows(window)
+            .map(|values| func.call1((PyList::new(py, values.iter().map(|x| x.to_f64()))?,))?.extract::<f64>())
+            .collect()
+    })
+}
@@ -0,0 +1,108 @@
+// Random sampling from streams and labelled data
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use pyo3::prelude::*;
+
+use crate::rng::Rng;
+use crate::stats::Keys;
+
+// Items between Ctrl-C checks while draining a Python iterator
+const SIGNAL_EVERY: usize = 1 << 16;
+
+// Uniform in (0, 1], safe to take the log of
+fn open_unit(rng: &mut Rng) -> f64 {
+    1.0 - rng.next_f64()
+}
+
+// Li's Algorithm L: after the reservoir fills, the gap to the next replacement is drawn
+// directly, so skipped items cost one `next()` each and no random draws
+#[pyfunction]
+#[pyo3(signature = (iterable, k, seed=None))]
+pub fn reservoir_sample(py: Python<'_>, iterable: &Bound<'_, PyAny>, k: usize, seed: Option<u64>) -> PyResult<Vec<Py<PyAny>>> {
+    if k == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("k must be positive"));
+    }
+    let mut rng = Rng::from_seed(seed);
+    let mut items = iterable.try_iter()?;
+    let mut reservoir: Vec<Py<PyAny>> = Vec::with_capacity(k);
+    for item in items.by_ref().take(k) {
+        reservoir.push(item?.unbind());
+    }
+    if reservoir.len() < k {
+        return Ok(reservoir);
+    }
+    let mut w = libm::exp(libm::log(open_unit(&mut rng)) / k as f64);
+    let mut seen = 0usize;
+    loop {
+        let skip = (libm::log(open_unit(&mut rng)) / libm::log1p(-w)).floor();
+        // A gap beyond usize only happens once w underflows; nothing further would be kept
+        if !skip.is_finite() || skip >= usize::MAX as f64 {
+            break;
+        }
+        for _ in 0..skip as usize {
+            if items.next().transpose()?.is_none() {
+                return Ok(reservoir);
+            }
+            seen += 1;
+            if seen.is_multiple_of(SIGNAL_EVERY) {
+                py.check_signals()?;
+            }
+        }
+        match items.next() {
+            Some(item) => reservoir[rng.below(k)] = item?.unbind(),
+            None => return Ok(reservoir),
+        }
+        w *= libm::exp(libm::log(open_unit(&mut rng)) / k as f64);
+    }
+    Ok(reservoir)
+}
+
+fn stratify<K: Hash + Eq + Ord>(labels: &[K], frac: f64, rng: &mut Rng) -> Vec<usize> {
+    let mut strata: HashMap<&K, Vec<usize>> = HashMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        strata.entry(label).or_default().push(i);
+    }
+    // Strata are visited in label order so a seed gives the same sample on every run
+    let mut strata: Vec<(&K, Vec<usize>)> = strata.into_iter().collect();
+    strata.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    let mut chosen = Vec::new();
+    for (_, mut members) in strata {
+        let take = ((frac * members.len() as f64).round() as usize).clamp(1, members.len());
+        // Partial Fisher-Yates: the first `take` slots end up a uniform sample
+        for i in 0..take {
+            let j = i + rng.below(members.len() - i);
+            members.swap(i, j);
+        }
+        chosen.extend_from_slice(&members[..take]);
+    }
+    chosen.sort_unstable();
+    chosen
+}
+
+// Samples round(frac * n) indices from each label's n rows, at least one per label, so the
+// sample keeps the label proportions; returned in ascending order
+#[pyfunction]
+#[pyo3(signature = (labels, frac, seed=None))]
+pub fn stratified_sample_indices(labels: Keys, frac: f64, seed: Option<u64>) -> PyResult<Vec<usize>> {
+    if !(frac > 0.0 && frac <= 1.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("frac must be in (0, 1]"));
+    }
+    let mut rng = Rng::from_seed(seed);
+    Ok(match &labels {
+        Keys::Ints(labels) => stratify(labels, frac, &mut rng),
+        Keys::Strings(labels) => stratify(labels, frac, &mut rng),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stratify_is_pinned_for_a_seed() {
+        let labels = [0, 1, 0, 1, 0, 1, 2, 2, 0, 1];
+        assert_eq!(stratify(&labels, 0.5, &mut Rng::new(11)), [0, 1, 2, 5, 6]);
+    }
+}
@@ -0,0 +1,327 @@
+// Float arrays in named OS shared memory (POSIX shm_open + mmap). Worker processes attach by
+// name, so nothing is pickled per worker. Every element is accessed as a relaxed atomic, which
+// lets threads of one process read and write concurrently through &self; array functions work on
+// a snapshot copied out in one pass. Writes are visible to all processes immediately;
+// coordinating them with readers is up to the caller
+
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use pyo3::prelude::*;
+
+use crate::input::{floats, ArrayLike, Element};
+use crate::with_slice;
+
+// The mapped region; the pointer stays valid until the mapping is dropped
+struct Mapping {
+    ptr: NonNull<f64>,
+    len: usize,
+}
+
+// SAFETY: the mapping is plain shared memory with no thread affinity
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+#[pyclass(module = "fina", frozen, sequence)]
+pub struct SharedArray {
+    name: String,
+    mapping: Mapping,
+}
+
+// Failures of the libc calls, kept free of PyErr so the mapping code runs without Python
+enum ShmError {
+    Invalid(&'static str),
+    Os(&'static str, std::io::Error),
+}
+
+impl ShmError {
+    fn os(what: &'static str) -> Self {
+        ShmError::Os(what, std::io::Error::last_os_error())
+    }
+
+    fn raise(self, name: &str) -> PyErr {
+        match self {
+            ShmError::Invalid(message) => pyo3::exceptions::PyValueError::new_err(message),
+            ShmError::Os(what, err) => pyo3::exceptions::PyOSError::new_err(format!("{} {:?}: {}", what, name, err)),
+        }
+    }
+}
+
+// POSIX names are a single leading slash followed by no further slashes
+fn posix_name(name: &str) -> Result<std::ffi::CString, ShmError> {
+    let bare = name.strip_prefix('/').unwrap_or(name);
+    if bare.is_empty() || bare.contains('/') {
+        return Err(ShmError::Invalid("name must be non-empty and contain no '/'"));
+    }
+    std::ffi::CString::new(format!("/{}", bare)).map_err(|_| ShmError::Invalid("name cannot contain NUL"))
+}
+
+// Bytes for len floats, if that fits both usize and the off_t that ftruncate takes
+#[cfg(unix)]
+fn byte_len(len: usize) -> Option<usize> {
+    len.checked_mul(8).filter(|&bytes| libc::off_t::try_from(bytes).is_ok())
+}
+
+#[cfg(unix)]
+fn map(name: &str, size: Option<usize>) -> Result<Mapping, ShmError> {
+    let c_name = posix_name(name)?;
+    let flags = match size {
+        Some(size) => {
+            if byte_len(size).is_none() {
+                return Err(ShmError::Invalid("size is too large for shared memory"));
+            }
+            libc::O_CREAT | libc::O_EXCL | libc::O_RDWR
+        }
+        None => libc::O_RDWR,
+    };
+    // SAFETY: plain libc calls on a descriptor this function owns; it is closed on every path
+    unsafe {
+        let fd = libc::shm_open(c_name.as_ptr(), flags, 0o600);
+        if fd < 0 {
+            return Err(ShmError::os(if size.is_some() { "Cannot create shared memory" } else { "Cannot attach shared memory" }));
+        }
+        let len = match size {
+            Some(len) => {
+                // byte_len was checked above, so the cast cannot truncate
+                if libc::ftruncate(fd, (len * 8) as libc::off_t) != 0 {
+                    let err = ShmError::os("Cannot size shared memory");
+                    libc::close(fd);
+                    libc::shm_unlink(c_name.as_ptr());
+                    return Err(err);
+                }
+                len
+            }
+            None => {
+                let mut stat: libc::stat = std::mem::zeroed();
+                if libc::fstat(fd, &mut stat) != 0 {
+                    let err = ShmError::os("Cannot stat shared memory");
+                    libc::close(fd);
+                    return Err(err);
+                }
+                match usize::try_from(stat.st_size) {
+                    Ok(bytes) => bytes / 8,
+                    Err(_) => {
+                        libc::close(fd);
+                        return Err(ShmError::Invalid("shared memory segment is too large to map"));
+                    }
+                }
+            }
+        };
+        let ptr = if len == 0 {
+            NonNull::dangling().as_ptr()
+        } else {
+            libc::mmap(std::ptr::null_mut(), len * 8, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED, fd, 0) as *mut f64
+        };
+        libc::close(fd);
+        if ptr as *mut libc::c_void == libc::MAP_FAILED {
+            return Err(ShmError::os("Cannot map shared memory"));
+        }
+        Ok(Mapping { ptr: NonNull::new(ptr).unwrap_or(NonNull::dangling()), len })
+    }
+}
+
+#[cfg(not(unix))]
+fn map(_name: &str, _size: Option<usize>) -> Result<Mapping, ShmError> {
+    Err(ShmError::Invalid("SharedArray needs POSIX shared memory"))
+}
+
+#[cfg(unix)]
+fn unlink(name: &str) -> Result<(), ShmError> {
+    let c_name = posix_name(name)?;
+    // SAFETY: c_name is a valid NUL-terminated string
+    if unsafe { libc::shm_unlink(c_name.as_ptr()) } != 0 {
+        return Err(ShmError::os("Cannot unlink shared memory"));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn unlink(_name: &str) -> Result<(), ShmError> {
+    Ok(())
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if self.len > 0 {
+            // SAFETY: ptr and len describe the region returned by mmap, whose byte length fit
+            // in off_t when it was created or in usize when it was attached
+            unsafe {
+                libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, self.len * 8);
+            }
+        }
+    }
+}
+
+impl SharedArray {
+    fn open(name: &str, size: Option<usize>) -> Result<Self, ShmError> {
+        Ok(SharedArray { name: name.to_string(), mapping: map(name, size)? })
+    }
+
+    fn cells(&self) -> &[AtomicU64] {
+        // SAFETY: the mapping holds len page-aligned 8-byte values for as long as self lives, and
+        // this process only ever touches them through these atomics
+        unsafe { std::slice::from_raw_parts(self.mapping.ptr.as_ptr() as *const AtomicU64, self.mapping.len) }
+    }
+
+    // The current contents; other writers may change the segment right after
+    pub fn to_vec(&self) -> Vec<f64> {
+        self.cells().iter().map(|cell| f64::from_bits(cell.load(Ordering::Relaxed))).collect()
+    }
+
+    fn cell(&self, index: isize) -> Option<&AtomicU64> {
+        let n = self.mapping.len as isize;
+        let i = if index < 0 { index + n } else { index };
+        if i < 0 || i >= n {
+            return None;
+        }
+        Some(&self.cells()[i as usize])
+    }
+
+    // False, writing nothing, when the values do not fit at offset
+    fn write_at<T: Element>(&self, values: &[T], offset: usize) -> bool {
+        let Some(cells) = offset.checked_add(values.len()).and_then(|end| self.cells().get(offset..end)) else {
+            return false;
+        };
+        for (cell, v) in cells.iter().zip(floats(values)) {
+            cell.store(v.to_bits(), Ordering::Relaxed);
+        }
+        true
+    }
+}
+
+fn out_of_range() -> PyErr {
+    pyo3::exceptions::PyIndexError::new_err("SharedArray index out of range")
+}
+
+#[pymethods]
+impl SharedArray {
+    // New zero-filled segment of `size` floats; fails if the name is taken
+    #[staticmethod]
+    fn create(name: &str, size: usize) -> PyResult<Self> {
+        SharedArray::open(name, Some(size)).map_err(|e| e.raise(name))
+    }
+
+    // Maps an existing segment, typically from a worker process
+    #[staticmethod]
+    fn attach(name: &str) -> PyResult<Self> {
+        SharedArray::open(name, None).map_err(|e| e.raise(name))
+    }
+
+    // Removes the name so no new process can attach; existing mappings stay valid until dropped
+    fn unlink(&self) -> PyResult<()> {
+        unlink(&self.name).map_err(|e| e.raise(&self.name))
+    }
+
+    // Copies values into the segment starting at offset
+    #[pyo3(signature = (values, offset=0))]
+    fn write(&self, values: ArrayLike, offset: usize) -> PyResult<()> {
+        if !with_slice!(values, values => self.write_at(values, offset)) {
+            return Err(pyo3::exceptions::PyValueError::new_err("values do not fit in the SharedArray at this offset"));
+        }
+        Ok(())
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn __len__(&self) -> usize {
+        self.mapping.len
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<f64> {
+        let cell = self.cell(index).ok_or_else(out_of_range)?;
+        Ok(f64::from_bits(cell.load(Ordering::Relaxed)))
+    }
+
+    fn __setitem__(&self, index: isize, value: f64) -> PyResult<()> {
+        self.cell(index).ok_or_else(out_of_range)?.store(value.to_bits(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn tolist(&self) -> Vec<f64> {
+        self.to_vec()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SharedArray(name={:?}, len={})", self.name, self.mapping.len)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    // Unique per process and test, and unlinked even when an assertion fails
+    struct Name(String);
+
+    impl Name {
+        fn new(test: &str) -> Self {
+            Name(format!("fina-test-{}-{}", std::process::id(), test))
+        }
+    }
+
+    impl Drop for Name {
+        fn drop(&mut self) {
+            let _ = unlink(&self.0);
+        }
+    }
+
+    fn open(name: &Name, size: Option<usize>) -> SharedArray {
+        match SharedArray::open(&name.0, size) {
+            Ok(array) => array,
+            Err(_) => panic!("cannot open shared memory {:?}", name.0),
+        }
+    }
+
+    #[test]
+    fn create_is_zeroed_and_attach_shares_writes() {
+        let name = Name::new("share");
+        let created = open(&name, Some(4));
+        assert_eq!(created.to_vec(), [0.0; 4]);
+        let attached = open(&name, None);
+        assert_eq!(attached.mapping.len, 4);
+        assert!(created.write_at(&[1.5f32, -2.0], 1));
+        attached.cell(-1).unwrap().store(7.0f64.to_bits(), Ordering::Relaxed);
+        assert_eq!(created.to_vec(), [0.0, 1.5, -2.0, 7.0]);
+        assert_eq!(attached.to_vec(), created.to_vec());
+        assert!(SharedArray::open(&name.0, Some(4)).is_err(), "create must not reuse a taken name");
+    }
+
+    #[test]
+    fn out_of_bounds_access_is_rejected() {
+        let name = Name::new("bounds");
+        let array = open(&name, Some(3));
+        assert!(array.cell(3).is_none() && array.cell(-4).is_none());
+        assert!(array.cell(2).is_some() && array.cell(-3).is_some());
+        assert!(!array.write_at(&[1.0, 2.0], 2));
+        assert!(!array.write_at(&[1.0], usize::MAX));
+        assert!(array.write_at(&[1.0, 2.0], 1));
+        assert!(array.write_at::<f64>(&[], 3));
+        assert_eq!(array.to_vec(), [0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn attach_sees_the_current_size_and_oversized_requests_fail() {
+        let name = Name::new("resize");
+        drop(open(&name, Some(2)));
+        // Grow the segment the way another process could, then attach to the new size
+        let c_name = posix_name(&name.0).unwrap_or_else(|_| panic!("bad name"));
+        // SAFETY: plain libc calls on a descriptor closed before the block ends
+        unsafe {
+            let fd = libc::shm_open(c_name.as_ptr(), libc::O_RDWR, 0o600);
+            assert!(fd >= 0);
+            assert_eq!(libc::ftruncate(fd, 5 * 8 + 3), 0);
+            libc::close(fd);
+        }
+        let attached = open(&name, None);
+        assert_eq!(attached.to_vec(), [0.0; 5]);
+
+        let empty = Name::new("empty");
+        assert_eq!(open(&empty, Some(0)).to_vec(), [0.0; 0]);
+        assert!(byte_len(usize::MAX / 8 + 1).is_none());
+        assert!(matches!(SharedArray::open(&Name::new("huge").0, Some(usize::MAX)), Err(ShmError::Invalid(_))));
+    }
+}
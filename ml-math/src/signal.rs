@@ -0,0 +1,495 @@
+use pyo3::prelude::*;
+
+use crate::errors::Invalid;
+
+// Daubechies scaling filters (reconstruction low-pass)
+const HAAR: [f64; 2] = [std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2];
+const DB4: [f64; 8] = [
+    0.230_377_813_308_855_23,
+    0.714_846_570_552_541_5,
+    0.630_880_767_929_590_4,
+    -0.027_983_769_416_983_85,
+    -0.187_034_811_718_881_14,
+    0.030_841_381_835_986_965,
+    0.032_883_011_666_982_945,
+    -0.010_597_401_784_997_278,
+];
+
+fn wavelet_filters(wavelet: &str) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let low: &[f64] = match wavelet {
+        "haar" => &HAAR,
+        "db4" => &DB4,
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("wavelet must be 'haar' or 'db4'")),
+    };
+    // Quadrature mirror high-pass filter
+    let len = low.len();
+    let high = (0..len).map(|k| if k % 2 == 0 { low[len - 1 - k] } else { -low[len - 1 - k] }).collect();
+    Ok((low.to_vec(), high))
+}
+
+// One level of the periodized orthogonal transform
+fn dwt_step(x: &[f64], low: &[f64], high: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let n = x.len();
+    let half = n / 2;
+    let mut approx = vec![0.0; half];
+    let mut detail = vec![0.0; half];
+    for i in 0..half {
+        for (k, (&h, &g)) in low.iter().zip(high.iter()).enumerate() {
+            let v = x[(2 * i + k) % n];
+            approx[i] += h * v;
+            detail[i] += g * v;
+        }
+    }
+    (approx, detail)
+}
+
+fn idwt_step(approx: &[f64], detail: &[f64], low: &[f64], high: &[f64]) -> Vec<f64> {
+    let n = approx.len() * 2;
+    let mut x = vec![0.0; n];
+    for i in 0..approx.len() {
+        for (k, (&h, &g)) in low.iter().zip(high.iter()).enumerate() {
+            x[(2 * i + k) % n] += h * approx[i] + g * detail[i];
+        }
+    }
+    x
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, wavelet="haar", levels=1))]
+pub fn dwt(data: Vec<f64>, wavelet: &str, levels: usize) -> PyResult<Vec<Vec<f64>>> {
+    let (low, high) = wavelet_filters(wavelet)?;
+    if levels == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("levels must be at least 1"));
+    }
+    if levels >= usize::BITS as usize || data.is_empty() || !data.len().is_multiple_of(1usize << levels) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data length must be a positive multiple of 2**levels"));
+    }
+
+    // Same layout as pywt.wavedec: [cA_n, cD_n, ..., cD_1]
+    let mut details = Vec::with_capacity(levels);
+    let mut approx = data;
+    for _ in 0..levels {
+        let (a, d) = dwt_step(&approx, &low, &high);
+        details.push(d);
+        approx = a;
+    }
+    let mut coeffs = vec![approx];
+    coeffs.extend(details.into_iter().rev());
+    Ok(coeffs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (coeffs, wavelet="haar"))]
+pub fn idwt(coeffs: Vec<Vec<f64>>, wavelet: &str) -> PyResult<Vec<f64>> {
+    let (low, high) = wavelet_filters(wavelet)?;
+    if coeffs.len() < 2 {
+        return Err(pyo3::exceptions::PyValueError::new_err("coeffs must hold an approximation and at least one detail level"));
+    }
+    let mut approx = coeffs[0].clone();
+    for detail in &coeffs[1..] {
+        if detail.len() != approx.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err("Coefficient lengths do not match"));
+        }
+        approx = idwt_step(&approx, detail, &low, &high);
+    }
+    Ok(approx)
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+
+    fn conj(self) -> Complex {
+        Complex::new(self.re, -self.im)
+    }
+
+    pub fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+}
+
+// Iterative radix-2 Cooley-Tukey; the length must be a power of two
+fn fft_radix2(buf: &mut [Complex], inverse: bool) {
+    let n = buf.len();
+    if n <= 1 {
+        return;
+    }
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            buf.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2.0 * std::f64::consts::PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let w = Complex::new((angle * k as f64).cos(), (angle * k as f64).sin());
+                let u = buf[start + k];
+                let v = buf[start + k + len / 2].mul(w);
+                buf[start + k] = Complex::new(u.re + v.re, u.im + v.im);
+                buf[start + k + len / 2] = Complex::new(u.re - v.re, u.im - v.im);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+// Unnormalized DFT of any length; non powers of two go through Bluestein's chirp-z
+pub fn fft(input: &[Complex], inverse: bool) -> Vec<Complex> {
+    let n = input.len();
+    if n.is_power_of_two() || n <= 1 {
+        let mut buf = input.to_vec();
+        fft_radix2(&mut buf, inverse);
+        return buf;
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let chirp: Vec<Complex> = (0..n)
+        .map(|k| {
+            // k^2 mod 2n keeps the angle accurate for long inputs
+            let k2 = ((k as u128 * k as u128) % (2 * n as u128)) as f64;
+            let angle = sign * std::f64::consts::PI * k2 / n as f64;
+            Complex::new(angle.cos(), angle.sin())
+        })
+        .collect();
+
+    let m = (2 * n - 1).next_power_of_two();
+    let mut a = vec![Complex::default(); m];
+    let mut b = vec![Complex::default(); m];
+    for k in 0..n {
+        a[k] = input[k].mul(chirp[k]);
+        b[k] = chirp[k].conj();
+        if k > 0 {
+            b[m - k] = chirp[k].conj();
+        }
+    }
+    fft_radix2(&mut a, false);
+    fft_radix2(&mut b, false);
+    for (x, y) in a.iter_mut().zip(b.iter()) {
+        *x = x.mul(*y);
+    }
+    fft_radix2(&mut a, true);
+    (0..n)
+        .map(|k| {
+            let v = a[k].mul(chirp[k]);
+            Complex::new(v.re / m as f64, v.im / m as f64)
+        })
+        .collect()
+}
+
+// Analytic signal: zero the negative frequencies and double the positive ones
+pub fn analytic_signal(data: &[f64]) -> Vec<Complex> {
+    let n = data.len();
+    let input: Vec<Complex> = data.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    let mut spectrum = fft(&input, false);
+    for (k, c) in spectrum.iter_mut().enumerate() {
+        let weight = if k == 0 || (n.is_multiple_of(2) && k == n / 2) {
+            1.0
+        } else if k < n.div_ceil(2) {
+            2.0
+        } else {
+            0.0
+        };
+        c.re *= weight;
+        c.im *= weight;
+    }
+    fft(&spectrum, true)
+        .into_iter()
+        .map(|c| Complex::new(c.re / n as f64, c.im / n as f64))
+        .collect()
+}
+
+#[pyfunction]
+pub fn hilbert(data: Vec<f64>) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    if data.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+    }
+    let analytic = analytic_signal(&data);
+    let envelope = analytic.iter().map(|c| c.abs()).collect();
+    let phase = analytic.iter().map(|c| c.arg()).collect();
+    Ok((envelope, phase))
+}
+
+// Hamming-windowed sinc low-pass; cutoff is a fraction of Nyquist
+fn lowpass_fir(cutoff: f64, n_taps: usize) -> Vec<f64> {
+    let mid = (n_taps - 1) as f64 / 2.0;
+    let mut taps: Vec<f64> = (0..n_taps)
+        .map(|i| {
+            let t = i as f64 - mid;
+            let sinc = if t == 0.0 { cutoff } else { (std::f64::consts::PI * cutoff * t).sin() / (std::f64::consts::PI * t) };
+            let window = 0.54 - 0.46 * (2.0 * std::f64::consts::PI * i as f64 / (n_taps - 1) as f64).cos();
+            sinc * window
+        })
+        .collect();
+    let total: f64 = taps.iter().sum();
+    taps.iter_mut().for_each(|t| *t /= total);
+    taps
+}
+
+#[pyfunction]
+pub fn decimate(data: Vec<f64>, factor: usize) -> PyResult<Vec<f64>> {
+    if factor == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("factor must be positive"));
+    }
+    if data.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+    }
+    if factor == 1 {
+        return Ok(data);
+    }
+
+    // Same anti-aliasing design as scipy's FIR decimate: 20 * factor + 1 taps, cutoff at 1 / factor.
+    // The symmetric filter is applied centred (zero phase) and renormalized at the edges.
+    let taps = lowpass_fir(1.0 / factor as f64, 20 * factor + 1);
+    let half = taps.len() / 2;
+    let n = data.len();
+    Ok((0..n)
+        .step_by(factor)
+        .map(|i| {
+            let mut acc = 0.0;
+            let mut weight = 0.0;
+            for (k, &t) in taps.iter().enumerate() {
+                let j = i as isize + k as isize - half as isize;
+                if j >= 0 && (j as usize) < n {
+                    acc += t * data[j as usize];
+                    weight += t;
+                }
+            }
+            acc / weight
+        })
+        .collect())
+}
+
+#[pyfunction]
+pub fn lttb_downsample(x: Vec<f64>, y: Vec<f64>, n_out: usize) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    if x.len() != y.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("x and y must be same length"));
+    }
+    if n_out == 0 {
+        return Err(Invalid::new("n_out", "must be positive").raise("lttb_downsample"));
+    }
+    let n = x.len();
+    if n_out >= n {
+        return Ok((x, y));
+    }
+    // Both endpoints are always kept, so fewer than 3 points leaves no bucket to choose from
+    if n_out < 3 {
+        return Err(Invalid::new("n_out", format!("must be at least 3 to downsample {} points", n)).raise("lttb_downsample"));
+    }
+
+    // Largest-Triangle-Three-Buckets: keep the endpoints, pick the point in each bucket
+    // forming the largest triangle with the last pick and the next bucket's centroid
+    let bucket = (n - 2) as f64 / (n_out - 2) as f64;
+    let mut out_x = Vec::with_capacity(n_out);
+    let mut out_y = Vec::with_capacity(n_out);
+    out_x.push(x[0]);
+    out_y.push(y[0]);
+    let mut a = 0;
+    for b in 0..n_out - 2 {
+        let start = (b as f64 * bucket) as usize + 1;
+        let end = (((b + 1) as f64 * bucket) as usize + 1).min(n - 1);
+        let next_end = (((b + 2) as f64 * bucket) as usize + 1).min(n);
+        let next: Vec<usize> = if next_end > end { (end..next_end).collect() } else { vec![n - 1] };
+        let avg_x = next.iter().map(|&i| x[i]).sum::<f64>() / next.len() as f64;
+        let avg_y = next.iter().map(|&i| y[i]).sum::<f64>() / next.len() as f64;
+
+        let mut best = start;
+        let mut best_area = -1.0;
+        for i in start..end {
+            let area = ((x[a] - avg_x) * (y[i] - y[a]) - (x[a] - x[i]) * (avg_y - y[a])).abs();
+            if area > best_area {
+                best_area = area;
+                best = i;
+            }
+        }
+        out_x.push(x[best]);
+        out_y.push(y[best]);
+        a = best;
+    }
+    out_x.push(x[n - 1]);
+    out_y.push(y[n - 1]);
+    Ok((out_x, out_y))
+}
+
+// Second-order section in transposed direct form II (first-order sections have b2 = a2 = 0)
+#[derive(Clone, Copy)]
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+impl Biquad {
+    fn run(&self, data: &mut [f64]) {
+        if data.is_empty() {
+            return;
+        }
+        // Start from the steady state for the first sample to avoid a startup transient
+        let x0 = data[0];
+        let gain = (self.b0 + self.b1 + self.b2) / (1.0 + self.a1 + self.a2);
+        let y0 = gain * x0;
+        let mut z2 = self.b2 * x0 - self.a2 * y0;
+        let mut z1 = self.b1 * x0 - self.a1 * y0 + z2;
+        for v in data.iter_mut() {
+            let x = *v;
+            let y = self.b0 * x + z1;
+            z1 = self.b1 * x - self.a1 * y + z2;
+            z2 = self.b2 * x - self.a2 * y;
+            *v = y;
+        }
+    }
+}
+
+// Butterworth sections via the bilinear transform with frequency prewarping. Pole pairs sit at
+// angles pi (2i + 1) / 2N from the negative real axis for even N; odd N has its real pole at
+// angle 0, which shifts the pairs to pi (2i + 2) / 2N = k pi / N
+fn butterworth(cutoff: f64, fs: f64, order: usize, highpass: bool) -> Vec<Biquad> {
+    let k = (std::f64::consts::PI * cutoff / fs).tan();
+    let mut sections = Vec::with_capacity(order.div_ceil(2));
+    for i in 0..order / 2 {
+        let angle = std::f64::consts::PI * (2 * i + 1 + order % 2) as f64 / (2 * order) as f64;
+        let q = 1.0 / (2.0 * angle.cos());
+        let norm = 1.0 / (1.0 + k / q + k * k);
+        let a1 = 2.0 * (k * k - 1.0) * norm;
+        let a2 = (1.0 - k / q + k * k) * norm;
+        let (b0, b1) = if highpass { (norm, -2.0 * norm) } else { (k * k * norm, 2.0 * k * k * norm) };
+        sections.push(Biquad { b0, b1, b2: b0, a1, a2 });
+    }
+    if order % 2 == 1 {
+        let norm = 1.0 / (1.0 + k);
+        let a1 = (k - 1.0) * norm;
+        let (b0, b1) = if highpass { (norm, -norm) } else { (k * norm, k * norm) };
+        sections.push(Biquad { b0, b1, b2: 0.0, a1, a2: 0.0 });
+    }
+    sections
+}
+
+fn apply_sections(data: Vec<f64>, sections: &[Biquad], zero_phase: bool) -> Vec<f64> {
+    if !zero_phase {
+        let mut out = data;
+        sections.iter().for_each(|s| s.run(&mut out));
+        return out;
+    }
+
+    // Forward-backward filtering over an odd-reflected extension of the edges, as filtfilt does
+    let n = data.len();
+    let pad = (3 * (2 * sections.len() + 1)).min(n - 1);
+    let mut extended = Vec::with_capacity(n + 2 * pad);
+    extended.extend((1..=pad).rev().map(|i| 2.0 * data[0] - data[i]));
+    extended.extend_from_slice(&data);
+    extended.extend((1..=pad).map(|i| 2.0 * data[n - 1] - data[n - 1 - i]));
+
+    sections.iter().for_each(|s| s.run(&mut extended));
+    extended.reverse();
+    sections.iter().for_each(|s| s.run(&mut extended));
+    extended.reverse();
+    extended[pad..pad + n].to_vec()
+}
+
+fn validate_filter_args(function: &str, data: &[f64], cutoffs: &[(&'static str, f64)], fs: f64, order: usize) -> PyResult<()> {
+    if data.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+    }
+    if fs <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("fs must be positive"));
+    }
+    if order == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("order must be at least 1"));
+    }
+    if let Some((argument, c)) = cutoffs.iter().find(|(_, c)| !(*c > 0.0 && *c < fs / 2.0)) {
+        return Err(Invalid::new(argument, format!("is {} but must be between 0 and fs / 2", c)).raise(function));
+    }
+    Ok(())
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, cutoff, fs, order=4, zero_phase=true))]
+pub fn lowpass(data: Vec<f64>, cutoff: f64, fs: f64, order: usize, zero_phase: bool) -> PyResult<Vec<f64>> {
+    validate_filter_args("lowpass", &data, &[("cutoff", cutoff)], fs, order)?;
+    Ok(apply_sections(data, &butterworth(cutoff, fs, order, false), zero_phase))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, cutoff, fs, order=4, zero_phase=true))]
+pub fn highpass(data: Vec<f64>, cutoff: f64, fs: f64, order: usize, zero_phase: bool) -> PyResult<Vec<f64>> {
+    validate_filter_args("highpass", &data, &[("cutoff", cutoff)], fs, order)?;
+    Ok(apply_sections(data, &butterworth(cutoff, fs, order, true), zero_phase))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, low, high, fs, order=4, zero_phase=true))]
+pub fn bandpass(data: Vec<f64>, low: f64, high: f64, fs: f64, order: usize, zero_phase: bool) -> PyResult<Vec<f64>> {
+    validate_filter_args("bandpass", &data, &[("low", low), ("high", high)], fs, order)?;
+    if low >= high {
+        return Err(pyo3::exceptions::PyValueError::new_err("low must be smaller than high"));
+    }
+    // High-pass at the lower edge cascaded with a low-pass at the upper edge
+    let mut sections = butterworth(low, fs, order, true);
+    sections.extend(butterworth(high, fs, order, false));
+    Ok(apply_sections(data, &sections, zero_phase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // |H(e^jw)| of the cascaded sections at frequency f
+    fn gain(sections: &[Biquad], f: f64, fs: f64) -> f64 {
+        let w = 2.0 * std::f64::consts::PI * f / fs;
+        sections
+            .iter()
+            .map(|s| {
+                // Numerator and denominator evaluated at z^-1 = e^-jw
+                let (c1, s1, c2, s2) = (w.cos(), -w.sin(), (2.0 * w).cos(), -(2.0 * w).sin());
+                let num = (s.b0 + s.b1 * c1 + s.b2 * c2, s.b1 * s1 + s.b2 * s2);
+                let den = (1.0 + s.a1 * c1 + s.a2 * c2, s.a1 * s1 + s.a2 * s2);
+                (num.0.hypot(num.1)) / den.0.hypot(den.1)
+            })
+            .product()
+    }
+
+    #[test]
+    fn butterworth_is_3db_down_at_cutoff() {
+        for order in 1..=6 {
+            for highpass in [false, true] {
+                let sections = butterworth(10.0, 100.0, order, highpass);
+                let g = gain(&sections, 10.0, 100.0);
+                assert!((g - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-9, "order {} highpass {}: {}", order, highpass, g);
+            }
+        }
+    }
+
+    #[test]
+    fn butterworth_passband_is_unity() {
+        for order in 1..=6 {
+            assert!((gain(&butterworth(10.0, 100.0, order, false), 0.0, 100.0) - 1.0).abs() < 1e-12);
+            assert!((gain(&butterworth(10.0, 100.0, order, true), 50.0, 100.0) - 1.0).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+// Sparse vectors as parallel (sorted indices, values) arrays
+
+use pyo3::prelude::*;
+
+use crate::input::{floats, ArrayLike};
+use crate::state::{persist_methods, persist_struct};
+use crate::with_slice;
+
+// Indices must be strictly increasing so the kernels can merge in one linear pass
+pub fn check_sparse(name: &str, indices: &[usize], values: &[f64]) -> PyResult<()> {
+    if indices.len() != values.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "indices_{0} and values_{0} must be the same length",
+            name
+        )));
+    }
+    if indices.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "indices_{} must be strictly increasing",
+            name
+        )));
+    }
+    Ok(())
+}
+
+pub fn dot_sorted(ia: &[usize], va: &[f64], ib: &[usize], vb: &[f64]) -> f64 {
+    let (mut i, mut j) = (0, 0);
+    let mut sum = 0.0;
+    while i < ia.len() && j < ib.len() {
+        match ia[i].cmp(&ib[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                sum += va[i] * vb[j];
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    sum
+}
+
+// None when either vector has zero norm
+pub fn cosine_sorted(ia: &[usize], va: &[f64], ib: &[usize], vb: &[f64]) -> Option<f64> {
+    let norm_a = va.iter().map(|v| v * v).sum::<f64>().sqrt();
+    let norm_b = vb.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm_a < f64::EPSILON || norm_b < f64::EPSILON {
+        return None;
+    }
+    Some(dot_sorted(ia, va, ib, vb) / (norm_a * norm_b))
+}
+
+#[pyfunction]
+pub fn sparse_dot(indices_a: Vec<usize>, values_a: Vec<f64>, indices_b: Vec<usize>, values_b: Vec<f64>) -> PyResult<f64> {
+    check_sparse("a", &indices_a, &values_a)?;
+    check_sparse("b", &indices_b, &values_b)?;
+    Ok(dot_sorted(&indices_a, &values_a, &indices_b, &values_b))
+}
+
+#[pyfunction]
+pub fn sparse_cosine(indices_a: Vec<usize>, values_a: Vec<f64>, indices_b: Vec<usize>, values_b: Vec<f64>) -> PyResult<f64> {
+    check_sparse("a", &indices_a, &values_a)?;
+    check_sparse("b", &indices_b, &values_b)?;
+    cosine_sorted(&indices_a, &values_a, &indices_b, &values_b)
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Cannot compute cosine similarity for zero vectors"))
+}
+
+// Right-hand side of SparseVector.dot: another SparseVector or a dense sequence of length dim
+#[derive(FromPyObject)]
+enum SparseOperand<'py> {
+    Sparse(PyRef<'py, SparseVector>),
+    Dense(Vec<f64>),
+}
+
+// Fixed-dimension sparse vector holding only its nonzero entries, indices ascending
+#[pyclass(module = "fina")]
+#[derive(Clone)]
+pub struct SparseVector {
+    dim: usize,
+    indices: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl SparseVector {
+    fn require_same_dim(&self, other: &SparseVector) -> PyResult<()> {
+        if self.dim != other.dim {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "SparseVector dimensions differ: {} and {}",
+                self.dim, other.dim
+            )));
+        }
+        Ok(())
+    }
+}
+
+persist_methods!(impl SparseVector {
+    // Entries may come in any order; explicit zeros are dropped and repeated indices rejected
+    #[new]
+    fn new(indices: Vec<usize>, values: Vec<f64>, dim: usize) -> PyResult<Self> {
+        if indices.len() != values.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err("indices and values must be the same length"));
+        }
+        let mut pairs: Vec<(usize, f64)> = indices.into_iter().zip(values).filter(|&(_, v)| v != 0.0).collect();
+        pairs.sort_unstable_by_key(|&(i, _)| i);
+        if pairs.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(pyo3::exceptions::PyValueError::new_err("indices must not repeat"));
+        }
+        if pairs.last().is_some_and(|&(i, _)| i >= dim) {
+            return Err(pyo3::exceptions::PyValueError::new_err("indices must be less than dim"));
+        }
+        let (indices, values) = pairs.into_iter().unzip();
+        Ok(SparseVector { dim, indices, values })
+    }
+
+    #[staticmethod]
+    fn from_dense(data: ArrayLike) -> Self {
+        let (indices, values) = with_slice!(data, data => {
+            floats(data).enumerate().filter(|&(_, v)| v != 0.0).unzip()
+        });
+        let dim = with_slice!(data, data => data.len());
+        SparseVector { dim, indices, values }
+    }
+
+    fn to_dense(&self) -> Vec<f64> {
+        let mut dense = vec![0.0; self.dim];
+        for (&i, &v) in self.indices.iter().zip(&self.values) {
+            dense[i] = v;
+        }
+        dense
+    }
+
+    #[getter]
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    #[getter]
+    fn nnz(&self) -> usize {
+        self.indices.len()
+    }
+
+    #[getter]
+    fn indices(&self) -> Vec<usize> {
+        self.indices.clone()
+    }
+
+    #[getter]
+    fn values(&self) -> Vec<f64> {
+        self.values.clone()
+    }
+
+    fn dot(&self, other: SparseOperand<'_>) -> PyResult<f64> {
+        match other {
+            SparseOperand::Sparse(other) => {
+                self.require_same_dim(&other)?;
+                Ok(dot_sorted(&self.indices, &self.values, &other.indices, &other.values))
+            }
+            SparseOperand::Dense(other) => {
+                if other.len() != self.dim {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "Dense operand has {} elements but dim is {}",
+                        other.len(),
+                        self.dim
+                    )));
+                }
+                Ok(self.indices.iter().zip(&self.values).map(|(&i, v)| v * other[i]).sum())
+            }
+        }
+    }
+
+    fn cosine(&self, other: PyRef<'_, SparseVector>) -> PyResult<f64> {
+        self.require_same_dim(&other)?;
+        cosine_sorted(&self.indices, &self.values, &other.indices, &other.values)
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Cannot compute cosine similarity for zero vectors"))
+    }
+
+    fn norm(&self) -> f64 {
+        self.values.iter().map(|v| v * v).sum::<f64>().sqrt()
+    }
+
+    fn __len__(&self) -> usize {
+        self.dim
+    }
+
+    // Binary search over the stored indices; missing entries read as 0.0
+    fn __getitem__(&self, index: isize) -> PyResult<f64> {
+        let n = self.dim as isize;
+        let i = if index < 0 { index + n } else { index };
+        if i < 0 || i >= n {
+            return Err(pyo3::exceptions::PyIndexError::new_err("SparseVector index out of range"));
+        }
+        Ok(self.indices.binary_search(&(i as usize)).map_or(0.0, |k| self.values[k]))
+    }
+
+    fn __eq__(&self, other: PyRef<'_, SparseVector>) -> bool {
+        self.dim == other.dim && self.indices == other.indices && self.values == other.values
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SparseVector(indices={:?}, values={:?}, dim={})", self.indices, self.values, self.dim)
+    }
+});
+
+persist_struct!(
+    SparseVector { dim, indices, values },
+    check = |v| {
+        v.indices.len() == v.values.len()
+            && v.indices.windows(2).all(|w| w[0] < w[1])
+            && v.indices.last().is_none_or(|&i| i < v.dim)
+    }
+);
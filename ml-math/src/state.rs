@@ -0,0 +1,207 @@
+// Compact binary state for pickling and to_bytes()/from_bytes(); a header of magic bytes,
+// format version and class name guards against loading the wrong blob
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+const MAGIC: &[u8; 4] = b"FINA";
+const VERSION: u8 = 1;
+
+pub struct Writer(Vec<u8>);
+
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+pub fn corrupt() -> PyErr {
+    pyo3::exceptions::PyValueError::new_err("Invalid or corrupted fina state")
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> PyResult<&[u8]> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.data.len()).ok_or_else(corrupt)?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+}
+
+pub trait Persist: Sized {
+    // Class name written into the header
+    const NAME: &'static str = "";
+
+    fn save(&self, w: &mut Writer);
+    fn load(r: &mut Reader) -> PyResult<Self>;
+}
+
+macro_rules! persist_le {
+    ($($t:ty),*) => {$(
+        impl Persist for $t {
+            fn save(&self, w: &mut Writer) {
+                w.0.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn load(r: &mut Reader) -> PyResult<Self> {
+                Ok(<$t>::from_le_bytes(r.take(std::mem::size_of::<$t>())?.try_into().unwrap()))
+            }
+        }
+    )*};
+}
+
+persist_le!(f64, u64, i64, u8);
+
+impl Persist for usize {
+    fn save(&self, w: &mut Writer) {
+        (*self as u64).save(w);
+    }
+
+    fn load(r: &mut Reader) -> PyResult<Self> {
+        usize::try_from(u64::load(r)?).map_err(|_| corrupt())
+    }
+}
+
+impl Persist for bool {
+    fn save(&self, w: &mut Writer) {
+        (*self as u8).save(w);
+    }
+
+    fn load(r: &mut Reader) -> PyResult<Self> {
+        match u8::load(r)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(corrupt()),
+        }
+    }
+}
+
+impl<T: Persist> Persist for Vec<T> {
+    fn save(&self, w: &mut Writer) {
+        self.len().save(w);
+        self.iter().for_each(|v| v.save(w));
+    }
+
+    fn load(r: &mut Reader) -> PyResult<Self> {
+        let n = usize::load(r)?;
+        // Every element takes at least one byte, so a larger count cannot be genuine
+        if n > r.remaining() {
+            return Err(corrupt());
+        }
+        (0..n).map(|_| T::load(r)).collect()
+    }
+}
+
+impl<A: Persist, B: Persist> Persist for (A, B) {
+    fn save(&self, w: &mut Writer) {
+        self.0.save(w);
+        self.1.save(w);
+    }
+
+    fn load(r: &mut Reader) -> PyResult<Self> {
+        Ok((A::load(r)?, B::load(r)?))
+    }
+}
+
+pub fn to_bytes<'py, T: Persist>(py: Python<'py>, value: &T) -> Bound<'py, PyBytes> {
+    let name = T::NAME;
+    let mut w = Writer(MAGIC.to_vec());
+    w.0.push(VERSION);
+    name.len().save(&mut w);
+    w.0.extend_from_slice(name.as_bytes());
+    value.save(&mut w);
+    PyBytes::new(py, &w.0)
+}
+
+pub fn from_bytes<T: Persist>(data: &[u8]) -> PyResult<T> {
+    let name = T::NAME;
+    let mut r = Reader { data, pos: 0 };
+    if r.take(4)? != MAGIC {
+        return Err(corrupt());
+    }
+    if u8::load(&mut r)? != VERSION {
+        return Err(pyo3::exceptions::PyValueError::new_err("Unsupported fina state version"));
+    }
+    let len = usize::load(&mut r)?;
+    let stored = r.take(len)?;
+    if stored != name.as_bytes() {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "State holds a {}, not a {}",
+            String::from_utf8_lossy(stored),
+            name
+        )));
+    }
+    let value = T::load(&mut r)?;
+    if r.remaining() != 0 {
+        return Err(corrupt());
+    }
+    Ok(value)
+}
+
+// Persist impl writing the listed fields in order; `check` rejects blobs that would break invariants
+macro_rules! persist_struct {
+    ($ty:ident { $($field:ident),* $(,)? } $(, check = $check:expr)?) => {
+        impl $crate::state::Persist for $ty {
+            const NAME: &'static str = stringify!($ty);
+
+            fn save(&self, w: &mut $crate::state::Writer) {
+                $($crate::state::Persist::save(&self.$field, w);)*
+            }
+
+            fn load(r: &mut $crate::state::Reader) -> PyResult<Self> {
+                let value = $ty { $($field: $crate::state::Persist::load(r)?),* };
+                $(
+                    let check: fn(&$ty) -> bool = $check;
+                    if !check(&value) {
+                        return Err($crate::state::corrupt());
+                    }
+                )?
+                Ok(value)
+            }
+        }
+    };
+}
+
+// #[pymethods] block for a Persist class with to_bytes()/from_bytes() and pickle support appended
+macro_rules! persist_methods {
+    (impl $ty:ident { $($body:tt)* }) => {
+        #[pyo3::pymethods]
+        impl $ty {
+            $($body)*
+
+            fn to_bytes<'py>(&self, py: pyo3::Python<'py>) -> pyo3::Bound<'py, pyo3::types::PyBytes> {
+                $crate::state::to_bytes(py, self)
+            }
+
+            #[staticmethod]
+            fn from_bytes(data: &[u8]) -> pyo3::PyResult<Self> {
+                $crate::state::from_bytes(data)
+            }
+
+            fn __getstate__<'py>(&self, py: pyo3::Python<'py>) -> pyo3::Bound<'py, pyo3::types::PyBytes> {
+                $crate::state::to_bytes(py, self)
+            }
+
+            fn __setstate__(&mut self, data: &[u8]) -> pyo3::PyResult<()> {
+                *self = $crate::state::from_bytes(data)?;
+                Ok(())
+            }
+
+            fn __reduce__<'py>(slf: &pyo3::Bound<'py, Self>) -> pyo3::PyResult<$crate::state::Reduced<'py>> {
+                $crate::state::reduce(slf)
+            }
+        }
+    };
+}
+
+pub type Reduced<'py> = (Bound<'py, PyAny>, (Bound<'py, PyBytes>,));
+
+// Unpickling goes through from_bytes since the constructors take required arguments
+pub fn reduce<'py, T: Persist + pyo3::PyClass>(slf: &Bound<'py, T>) -> PyResult<Reduced<'py>> {
+    let state = to_bytes(slf.py(), &*slf.borrow());
+    Ok((slf.as_any().get_type().getattr("from_bytes")?, (state,)))
+}
+
+pub(crate) use {persist_methods, persist_struct};
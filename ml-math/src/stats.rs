@@ -0,0 +1,809 @@
+// Descriptive statistics built on streaming accumulators
+
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::hash::Hash;
+
+use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+use rayon::prelude::*;
+
+use crate::errors::{all_finite, same_length, Invalid};
+use crate::input::{detach_large, floats, ArrayLike, Element, Values};
+use crate::matrix::Matrix;
+use crate::parallel::{parallel, CHUNK};
+use crate::state::{persist_methods, persist_struct};
+use crate::with_slice;
+
+// Welford's running mean and sum of squared deviations, plus the extremes; numerically
+// stable in one pass, and two accumulators merge exactly (Chan et al.)
+#[derive(Clone, Copy)]
+pub struct Welford {
+    pub count: u64,
+    pub mean: f64,
+    pub m2: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Welford {
+    pub fn new() -> Self {
+        Welford { count: 0, mean: 0.0, m2: 0.0, min: f64::INFINITY, max: f64::NEG_INFINITY }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    pub fn merge(&mut self, other: &Welford) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 += other.m2 + delta * delta * (self.count as f64 * other.count as f64 / count as f64);
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    // Population variance, matching `variance`; 0.0 for fewer than two observations
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / self.count as f64
+        }
+    }
+}
+
+// Group keys: integers or strings
+#[derive(FromPyObject)]
+pub enum Keys {
+    Ints(Vec<i64>),
+    Strings(Vec<String>),
+}
+
+fn group_chunk<K: Hash + Eq + Clone, T: Element>(keys: &[K], values: &[T]) -> HashMap<K, Welford> {
+    let mut groups: HashMap<K, Welford> = HashMap::new();
+    for (key, x) in keys.iter().zip(floats(values)) {
+        match groups.get_mut(key) {
+            Some(acc) => acc.push(x),
+            None => {
+                let mut acc = Welford::new();
+                acc.push(x);
+                groups.insert(key.clone(), acc);
+            }
+        }
+    }
+    groups
+}
+
+// Fixed-size chunks are accumulated, in parallel for large inputs, and merged in chunk order so
+// the result does not depend on the thread count
+fn accumulate<K: Hash + Eq + Ord + Clone + Send + Sync, T: Element>(keys: &[K], values: &[T]) -> (Vec<K>, Vec<Welford>) {
+    let chunks: Vec<HashMap<K, Welford>> = if parallel(keys.len()) {
+        keys.par_chunks(CHUNK).zip(values.par_chunks(CHUNK)).map(|(k, v)| group_chunk(k, v)).collect()
+    } else {
+        keys.chunks(CHUNK).zip(values.chunks(CHUNK)).map(|(k, v)| group_chunk(k, v)).collect()
+    };
+    let mut groups: HashMap<K, Welford> = HashMap::new();
+    for chunk in chunks {
+        for (key, acc) in chunk {
+            groups.entry(key).or_insert_with(Welford::new).merge(&acc);
+        }
+    }
+    let mut groups: Vec<(K, Welford)> = groups.into_iter().collect();
+    groups.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    groups.into_iter().unzip()
+}
+
+pub type GroupedStats = (Py<PyAny>, Vec<u64>, Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>);
+
+// One pass over (key, value) pairs with a Welford accumulator per group; returns
+// (keys, count, mean, std, min, max) with the groups in ascending key order
+#[pyfunction]
+pub fn grouped_stats(py: Python<'_>, keys: Keys, values: ArrayLike) -> PyResult<GroupedStats> {
+    let n_keys = match &keys {
+        Keys::Ints(k) => k.len(),
+        Keys::Strings(k) => k.len(),
+    };
+    with_slice!(values, values => {
+        if n_keys != values.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "keys has {} elements but values has {}",
+                n_keys,
+                values.len()
+            )));
+        }
+        let (keys, groups) = match &keys {
+            Keys::Ints(k) => {
+                let (k, g) = detach_large(py, values.len(), || accumulate(k, values));
+                (k.into_py_any(py)?, g)
+            }
+            Keys::Strings(k) => {
+                let (k, g) = detach_large(py, values.len(), || accumulate(k, values));
+                (k.into_py_any(py)?, g)
+            }
+        };
+        Ok((
+            keys,
+            groups.iter().map(|g| g.count).collect(),
+            groups.iter().map(|g| g.mean).collect(),
+            groups.iter().map(|g| g.variance().sqrt()).collect(),
+            groups.iter().map(|g| g.min).collect(),
+            groups.iter().map(|g| g.max).collect(),
+        ))
+    })
+}
+
+// One probability or a list of them; results come back in the same shape
+#[derive(FromPyObject)]
+pub enum Probabilities {
+    One(f64),
+    Many(Vec<f64>),
+}
+
+// (value, cumulative weight) sorted by value, after validating both inputs
+fn weighted_cdf<V: Element, W: Element>(values: &[V], weights: &[W]) -> Result<(Vec<(f64, f64)>, f64), Invalid> {
+    same_length("values", values.len(), "weights", weights.len())?;
+    all_finite("values", values)?;
+    if let Some((i, w)) = floats(weights).enumerate().find(|(_, w)| !(w.is_finite() && *w >= 0.0)) {
+        return Err(Invalid::at("weights", i, w, "is not a finite non-negative weight"));
+    }
+    let mut pairs: Vec<(f64, f64)> = floats(values).zip(floats(weights)).filter(|p| p.1 > 0.0).collect();
+    if pairs.is_empty() {
+        return Err(Invalid::new("weights", "sum to zero"));
+    }
+    pairs.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+    let mut cumulative = 0.0;
+    for pair in pairs.iter_mut() {
+        cumulative += pair.1;
+        pair.1 = cumulative;
+    }
+    Ok((pairs, cumulative))
+}
+
+// "inverted_cdf": the smallest value whose cumulative weight reaches q * total, as numpy's
+// weighted quantile. "interpolated": each value sits at the midpoint of its weight,
+// (cumulative - w / 2) / total, with linear interpolation in between
+fn cdf_quantile(cdf: &[(f64, f64)], total: f64, q: f64, interpolated: bool) -> f64 {
+    let target = q * total;
+    if !interpolated {
+        // The relative slack keeps round-off in q * total from skipping past an exact tie
+        let i = cdf.partition_point(|&(_, c)| c < target * (1.0 - 1e-12));
+        return cdf[i.min(cdf.len() - 1)].0;
+    }
+    let mut prev: Option<(f64, f64)> = None;
+    let mut before = 0.0;
+    for &(value, cumulative) in cdf {
+        let centre = (before + cumulative) / 2.0;
+        if target <= centre {
+            return match prev {
+                Some((v0, c0)) => v0 + (value - v0) * (target - c0) / (centre - c0),
+                None => value,
+            };
+        }
+        prev = Some((value, centre));
+        before = cumulative;
+    }
+    cdf[cdf.len() - 1].0
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, weights, q, method="inverted_cdf"))]
+pub fn weighted_quantile(py: Python<'_>, values: ArrayLike, weights: ArrayLike, q: Probabilities, method: &str) -> PyResult<Py<PyAny>> {
+    let interpolated = match method {
+        "inverted_cdf" => false,
+        "interpolated" => true,
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("method must be 'inverted_cdf' or 'interpolated'")),
+    };
+    let (cdf, total) = with_slice!(values, values => with_slice!(weights, weights => {
+        detach_large(py, values.len(), || weighted_cdf(values, weights))
+    }))
+    .map_err(|e| e.raise("weighted_quantile"))?;
+    let check = |q: f64| {
+        if (0.0..=1.0).contains(&q) {
+            Ok(cdf_quantile(&cdf, total, q, interpolated))
+        } else {
+            Err(pyo3::exceptions::PyValueError::new_err("q must be between 0 and 1"))
+        }
+    };
+    match q {
+        Probabilities::One(q) => check(q)?.into_py_any(py),
+        Probabilities::Many(qs) => qs.into_iter().map(check).collect::<PyResult<Vec<f64>>>()?.into_py_any(py),
+    }
+}
+
+#[pyfunction]
+pub fn weighted_median(py: Python<'_>, values: ArrayLike, weights: ArrayLike) -> PyResult<f64> {
+    with_slice!(values, values => with_slice!(weights, weights => {
+        detach_large(py, values.len(), || weighted_cdf(values, weights))
+    }))
+    .map(|(cdf, total)| cdf_quantile(&cdf, total, 0.5, false))
+    .map_err(|e| e.raise("weighted_median"))
+}
+
+// Weighted mean and total weight, after the same validation as the weighted quantiles
+fn weighted_mean_of<D: Element, W: Element>(data: &[D], weights: &[W]) -> Result<(f64, f64), Invalid> {
+    same_length("data", data.len(), "weights", weights.len())?;
+    all_finite("data", data)?;
+    if let Some((i, w)) = floats(weights).enumerate().find(|(_, w)| !(w.is_finite() && *w >= 0.0)) {
+        return Err(Invalid::at("weights", i, w, "is not a finite non-negative weight"));
+    }
+    let total: f64 = floats(weights).sum();
+    if total <= 0.0 {
+        return Err(Invalid::new("weights", "sum to zero"));
+    }
+    Ok((floats(data).zip(floats(weights)).map(|(x, w)| x * w).sum::<f64>() / total, total))
+}
+
+// sum(w * (x - mean)^2) / (sum(w) - ddof), the frequency-weight convention of statsmodels'
+// DescrStatsW; ddof=0 gives the population variance that matches `variance` for equal weights
+fn weighted_var<D: Element, W: Element>(data: &[D], weights: &[W], ddof: f64) -> Result<f64, Invalid> {
+    let (mean, total) = weighted_mean_of(data, weights)?;
+    if !(ddof >= 0.0 && ddof < total) {
+        return Err(Invalid::new("ddof", format!("must be non-negative and less than the total weight ({})", total)));
+    }
+    Ok(floats(data).zip(floats(weights)).map(|(x, w)| w * (x - mean).powi(2)).sum::<f64>() / (total - ddof))
+}
+
+#[pyfunction]
+pub fn weighted_mean(py: Python<'_>, data: ArrayLike, weights: ArrayLike) -> PyResult<f64> {
+    with_slice!(data, data => with_slice!(weights, weights => {
+        detach_large(py, data.len(), || weighted_mean_of(data, weights))
+    }))
+    .map(|(mean, _)| mean)
+    .map_err(|e| e.raise("weighted_mean"))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, weights, ddof=0.0))]
+pub fn weighted_variance(py: Python<'_>, data: ArrayLike, weights: ArrayLike, ddof: f64) -> PyResult<f64> {
+    with_slice!(data, data => with_slice!(weights, weights => {
+        detach_large(py, data.len(), || weighted_var(data, weights, ddof))
+    }))
+    .map_err(|e| e.raise("weighted_variance"))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, weights, ddof=0.0))]
+pub fn weighted_std(py: Python<'_>, data: ArrayLike, weights: ArrayLike, ddof: f64) -> PyResult<f64> {
+    with_slice!(data, data => with_slice!(weights, weights => {
+        detach_large(py, data.len(), || weighted_var(data, weights, ddof))
+    }))
+    .map(f64::sqrt)
+    .map_err(|e| e.raise("weighted_std"))
+}
+
+// Maps values with the given period onto radians and checks them
+fn to_radians<T: Element>(argument: &'static str, values: &[T], period: f64) -> Result<Vec<f64>, Invalid> {
+    if values.is_empty() {
+        return Err(Invalid::new(argument, "cannot be empty"));
+    }
+    if !(period.is_finite() && period > 0.0) {
+        return Err(Invalid::new("period", "must be positive"));
+    }
+    all_finite(argument, values)?;
+    Ok(floats(values).map(|v| v * TAU / period).collect())
+}
+
+// Mean resultant vector (mean cos, mean sin) of angles in radians
+fn resultant(radians: &[f64]) -> (f64, f64) {
+    let n = radians.len() as f64;
+    let (c, s) = radians.iter().fold((0.0, 0.0), |(c, s), a| (c + a.cos(), s + a.sin()));
+    (c / n, s / n)
+}
+
+fn circular_mean_slice<T: Element>(angles: &[T], period: f64) -> Result<f64, Invalid> {
+    let radians = to_radians("angles", angles, period)?;
+    let (c, s) = resultant(&radians);
+    if c.hypot(s) < 1e-12 {
+        return Err(Invalid::new("angles", "are spread uniformly, so the mean direction is undefined"));
+    }
+    let mean = s.atan2(c).rem_euclid(TAU) * period / TAU;
+    // A direction a hair below zero can round up to exactly the period
+    Ok(if mean >= period { 0.0 } else { mean })
+}
+
+// Direction of the mean resultant vector, in [0, period); angles default to radians, pass
+// period=24 for hours of the day or 360 for degrees
+#[pyfunction]
+#[pyo3(signature = (angles, period=TAU))]
+pub fn circular_mean(py: Python<'_>, angles: ArrayLike, period: f64) -> PyResult<f64> {
+    with_slice!(angles, angles => detach_large(py, angles.len(), || circular_mean_slice(angles, period)))
+        .map_err(|e| e.raise("circular_mean"))
+}
+
+fn circular_std_slice<T: Element>(angles: &[T], period: f64) -> Result<f64, Invalid> {
+    let radians = to_radians("angles", angles, period)?;
+    let (c, s) = resultant(&radians);
+    let r = c.hypot(s).min(1.0);
+    Ok((-2.0 * r.ln()).sqrt() * period / TAU)
+}
+
+// sqrt(-2 ln R) for mean resultant length R, in the same units as the angles
+#[pyfunction]
+#[pyo3(signature = (angles, period=TAU))]
+pub fn circular_std(py: Python<'_>, angles: ArrayLike, period: f64) -> PyResult<f64> {
+    with_slice!(angles, angles => detach_large(py, angles.len(), || circular_std_slice(angles, period)))
+        .map_err(|e| e.raise("circular_std"))
+}
+
+fn circular_corr_slice<A: Element, B: Element>(a: &[A], b: &[B], period: f64) -> Result<f64, Invalid> {
+    same_length("a", a.len(), "b", b.len())?;
+    let a = to_radians("a", a, period)?;
+    let b = to_radians("b", b, period)?;
+    let mean_angle = |r: &[f64]| {
+        let (c, s) = resultant(r);
+        s.atan2(c)
+    };
+    let (mean_a, mean_b) = (mean_angle(&a), mean_angle(&b));
+    let (mut num, mut den_a, mut den_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(&b) {
+        let (sa, sb) = ((x - mean_a).sin(), (y - mean_b).sin());
+        num += sa * sb;
+        den_a += sa * sa;
+        den_b += sb * sb;
+    }
+    if den_a == 0.0 {
+        return Err(Invalid::new("a", "does not vary, so the correlation is undefined"));
+    }
+    if den_b == 0.0 {
+        return Err(Invalid::new("b", "does not vary, so the correlation is undefined"));
+    }
+    Ok(num / (den_a * den_b).sqrt())
+}
+
+// Jammalamadaka-SenGupta circular correlation: the Pearson form applied to sines of the
+// deviations from each series' circular mean
+#[pyfunction]
+#[pyo3(signature = (a, b, period=TAU))]
+pub fn circular_corr(py: Python<'_>, a: ArrayLike, b: ArrayLike, period: f64) -> PyResult<f64> {
+    with_slice!(a, a => with_slice!(b, b => detach_large(py, a.len(), || circular_corr_slice(a, b, period))))
+        .map_err(|e| e.raise("circular_corr"))
+}
+
+// Most frequent value. With tol > 0, sorted values within tol of the first value of their run
+// count as one and the run's mean is reported. Ties give the smallest mode, or all of them in
+// ascending order with all_modes=True
+#[pyfunction]
+#[pyo3(signature = (data, tol=0.0, all_modes=false))]
+pub fn mode(py: Python<'_>, data: ArrayLike, tol: f64, all_modes: bool) -> PyResult<Py<PyAny>> {
+    let modes = with_slice!(data, data => detach_large(py, data.len(), || modes_slice(data, tol)))
+        .map_err(|e| e.raise("mode"))?;
+    if all_modes {
+        modes.into_py_any(py)
+    } else {
+        modes.first().copied().unwrap_or(f64::NAN).into_py_any(py)
+    }
+}
+
+// Every mode in ascending order
+fn modes_slice<T: Element>(data: &[T], tol: f64) -> Result<Vec<f64>, Invalid> {
+    if data.is_empty() {
+        return Err(Invalid::new("data", "cannot be empty"));
+    }
+    if !(tol.is_finite() && tol >= 0.0) {
+        return Err(Invalid::new("tol", "must be non-negative"));
+    }
+    all_finite("data", data)?;
+    let mut sorted: Vec<f64> = floats(data).collect();
+    sorted.sort_unstable_by(f64::total_cmp);
+    // (count, representative) per run
+    let mut runs: Vec<(usize, f64)> = Vec::new();
+    let mut start = 0;
+    for i in 1..=sorted.len() {
+        if i == sorted.len() || sorted[i] - sorted[start] > tol {
+            let run = &sorted[start..i];
+            runs.push((run.len(), run.iter().sum::<f64>() / run.len() as f64));
+            start = i;
+        }
+    }
+    let best = runs.iter().map(|r| r.0).max().unwrap_or(0);
+    Ok(runs.into_iter().filter(|r| r.0 == best).map(|r| r.1).collect())
+}
+
+// Central moments m2, m3, m4 (divided by n)
+fn central_moments<T: Element>(data: &[T]) -> Result<(f64, f64, f64), Invalid> {
+    if data.is_empty() {
+        return Err(Invalid::new("data", "cannot be empty"));
+    }
+    all_finite("data", data)?;
+    let n = data.len() as f64;
+    let mean = floats(data).sum::<f64>() / n;
+    let (m2, m3, m4) = floats(data).fold((0.0, 0.0, 0.0), |(m2, m3, m4), x| {
+        let d = x - mean;
+        let d2 = d * d;
+        (m2 + d2, m3 + d2 * d, m4 + d2 * d2)
+    });
+    // Spread at round-off level of the mean is treated as none
+    if m2 / n <= (f64::EPSILON * mean.abs()).powi(2) {
+        return Err(Invalid::new("data", "is constant, so its shape is undefined"));
+    }
+    Ok((m2 / n, m3 / n, m4 / n))
+}
+
+fn skewness_slice<T: Element>(data: &[T], bias: bool) -> Result<f64, Invalid> {
+    let (m2, m3, _) = central_moments(data)?;
+    let g1 = m3 / m2.powf(1.5);
+    if bias {
+        return Ok(g1);
+    }
+    let n = data.len() as f64;
+    if data.len() < 3 {
+        return Err(Invalid::new("data", "needs at least 3 values for bias-corrected skewness"));
+    }
+    Ok(g1 * (n * (n - 1.0)).sqrt() / (n - 2.0))
+}
+
+fn kurtosis_slice<T: Element>(data: &[T], fisher: bool, bias: bool) -> Result<f64, Invalid> {
+    let (m2, _, m4) = central_moments(data)?;
+    let mut excess = m4 / (m2 * m2) - 3.0;
+    if !bias {
+        let n = data.len() as f64;
+        if data.len() < 4 {
+            return Err(Invalid::new("data", "needs at least 4 values for bias-corrected kurtosis"));
+        }
+        excess = ((n + 1.0) * excess + 6.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0));
+    }
+    Ok(if fisher { excess } else { excess + 3.0 })
+}
+
+// Moment coefficient of skewness g1 = m3 / m2^1.5; bias=False applies the adjusted
+// Fisher-Pearson correction (needs at least 3 values), as scipy.stats.skew
+#[pyfunction]
+#[pyo3(signature = (data, bias=true))]
+pub fn skewness(py: Python<'_>, data: ArrayLike, bias: bool) -> PyResult<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || skewness_slice(data, bias))).map_err(|e| e.raise("skewness"))
+}
+
+// m4 / m2^2, minus 3 with fisher=True so a normal sample scores 0; bias=False gives the
+// unbiased estimator under normality (needs at least 4 values), as scipy.stats.kurtosis
+#[pyfunction]
+#[pyo3(signature = (data, fisher=true, bias=true))]
+pub fn kurtosis(py: Python<'_>, data: ArrayLike, fisher: bool, bias: bool) -> PyResult<f64> {
+    with_slice!(data, data => detach_large(py, data.len(), || kurtosis_slice(data, fisher, bias)))
+        .map_err(|e| e.raise("kurtosis"))
+}
+
+// Deviations from the mean of one series
+fn centered<T: Element>(argument: &'static str, data: &[T]) -> Result<Vec<f64>, Invalid> {
+    all_finite(argument, data)?;
+    let mean = floats(data).sum::<f64>() / data.len() as f64;
+    Ok(floats(data).map(|x| x - mean).collect())
+}
+
+// Centered copies of equal-length series with more than ddof observations
+fn centered_columns(columns: &[Values], ddof: usize) -> Result<Vec<Vec<f64>>, Invalid> {
+    let n = columns[0].len();
+    if let Some(i) = columns.iter().position(|c| c.len() != n) {
+        let reason = format!("has {} values in series {} but {} in the first", columns[i].len(), i, n);
+        return Err(Invalid::new("columns", reason));
+    }
+    if n <= ddof {
+        return Err(Invalid::new("ddof", format!("must be less than the number of observations ({})", n)));
+    }
+    columns
+        .iter()
+        .map(|c| match c {
+            Values::F64(values) => centered("data", values),
+            Values::F32(values) => centered("data", values),
+        })
+        .collect()
+}
+
+// Entry (i, j) is sum(d_i * d_j) / (n - ddof) over the centered columns
+fn covariances(centered: &[Vec<f64>], ddof: usize) -> Matrix {
+    let k = centered.len();
+    let denom = (centered[0].len() - ddof) as f64;
+    let mut cov = Matrix::zeros(k, k);
+    for i in 0..k {
+        for j in i..k {
+            let v = centered[i].iter().zip(&centered[j]).map(|(x, y)| x * y).sum::<f64>() / denom;
+            cov.data[i * k + j] = v;
+            cov.data[j * k + i] = v;
+        }
+    }
+    cov
+}
+
+// Sample covariance by default (ddof=1), as numpy.cov and pandas; ddof=0 for the population
+// version that matches `variance`
+#[pyfunction]
+#[pyo3(signature = (a, b, ddof=1))]
+pub fn covariance(py: Python<'_>, a: ArrayLike, b: ArrayLike, ddof: usize) -> PyResult<f64> {
+    with_slice!(a, a => with_slice!(b, b => {
+        detach_large(py, a.len(), || covariance_slice(a, b, ddof))
+    }))
+    .map_err(|e| e.raise("covariance"))
+}
+
+fn covariance_slice<A: Element, B: Element>(a: &[A], b: &[B], ddof: usize) -> Result<f64, Invalid> {
+    same_length("a", a.len(), "b", b.len())?;
+    if a.len() <= ddof {
+        return Err(Invalid::new("ddof", format!("must be less than the number of observations ({})", a.len())));
+    }
+    let centered = [centered("a", a)?, centered("b", b)?];
+    Ok(covariances(&centered, ddof).get(0, 1))
+}
+
+// Covariance matrix of a list of equal-length series, one series per row and column
+#[pyfunction]
+#[pyo3(signature = (columns, ddof=1))]
+pub fn cov_matrix(py: Python<'_>, columns: Vec<ArrayLike>, ddof: usize) -> PyResult<Matrix> {
+    if columns.is_empty() {
+        return Err(Invalid::new("columns", "cannot be empty").raise("cov_matrix"));
+    }
+    let values: Vec<Values> = columns.iter().map(ArrayLike::values).collect();
+    let work = columns.len() * columns.len() * values[0].len();
+    detach_large(py, work, || centered_columns(&values, ddof).map(|c| covariances(&c, ddof))).map_err(|e| e.raise("cov_matrix"))
+}
+
+// A constant series has no correlation; checked on the raw values since centering leaves round-off
+fn is_constant<T: Element>(data: &[T]) -> bool {
+    floats(data).all(|x| x == data[0].to_f64())
+}
+
+// Rescales a covariance matrix of non-constant series to correlations
+fn correlations(mut cov: Matrix) -> Matrix {
+    let k = cov.rows;
+    let scale: Vec<f64> = (0..k).map(|i| cov.get(i, i).sqrt()).collect();
+    for i in 0..k {
+        for j in 0..k {
+            // Clamped so round-off cannot push |r| past 1
+            cov.data[i * k + j] = if i == j { 1.0 } else { (cov.data[i * k + j] / (scale[i] * scale[j])).clamp(-1.0, 1.0) };
+        }
+    }
+    cov
+}
+
+fn correlation<A: Element, B: Element>(a: &[A], b: &[B]) -> Result<f64, Invalid> {
+    same_length("a", a.len(), "b", b.len())?;
+    if a.len() < 2 {
+        return Err(Invalid::new("a", "needs at least 2 observations"));
+    }
+    let centered = [centered("a", a)?, centered("b", b)?];
+    if is_constant(a) {
+        return Err(Invalid::new("a", "is constant, so the correlation is undefined"));
+    }
+    if is_constant(b) {
+        return Err(Invalid::new("b", "is constant, so the correlation is undefined"));
+    }
+    Ok(correlations(covariances(&centered, 1)).get(0, 1))
+}
+
+// Pearson product-moment correlation of two series
+#[pyfunction]
+pub fn pearson(py: Python<'_>, a: ArrayLike, b: ArrayLike) -> PyResult<f64> {
+    with_slice!(a, a => with_slice!(b, b => {
+        detach_large(py, a.len(), || correlation(a, b))
+    }))
+    .map_err(|e| e.raise("pearson"))
+}
+
+// 1-based ranks; each run of tied values gets the average of the ranks it spans
+fn average_ranks<T: Element>(data: &[T]) -> Vec<f64> {
+    let value = |i: usize| data[i].to_f64();
+    let mut order: Vec<usize> = (0..data.len()).collect();
+    order.sort_unstable_by(|&i, &j| value(i).total_cmp(&value(j)));
+    let mut ranks = vec![0.0; data.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let end = start + order[start..].iter().take_while(|&&i| value(i) == value(order[start])).count();
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+// Spearman rank correlation: Pearson on average ranks, so any monotonic relationship scores +-1
+#[pyfunction]
+pub fn spearman(py: Python<'_>, a: ArrayLike, b: ArrayLike) -> PyResult<f64> {
+    with_slice!(a, a => with_slice!(b, b => {
+        detach_large(py, a.len(), || spearman_slice(a, b))
+    }))
+    .map_err(|e| e.raise("spearman"))
+}
+
+fn spearman_slice<A: Element, B: Element>(a: &[A], b: &[B]) -> Result<f64, Invalid> {
+    same_length("a", a.len(), "b", b.len())?;
+    all_finite("a", a)?;
+    all_finite("b", b)?;
+    correlation(&average_ranks(a), &average_ranks(b))
+}
+
+// Pairs that tie within each run of equal values: sum of t(t - 1) / 2
+fn tied_pairs<T: PartialEq>(sorted: impl Iterator<Item = T>) -> u64 {
+    let (mut total, mut run, mut last) = (0u64, 0u64, None);
+    for value in sorted {
+        if last.as_ref() == Some(&value) {
+            run += 1;
+        } else {
+            run = 1;
+            last = Some(value);
+        }
+        total += run - 1;
+    }
+    total
+}
+
+// Merge sort that counts how many swaps (inversions) ordering the slice takes
+fn count_inversions(values: &mut [f64], buffer: &mut [f64]) -> u64 {
+    let n = values.len();
+    if n < 2 {
+        return 0;
+    }
+    let mid = n / 2;
+    let mut swaps = count_inversions(&mut values[..mid], &mut buffer[..mid]) + count_inversions(&mut values[mid..], &mut buffer[mid..]);
+    let (mut i, mut j) = (0, mid);
+    for slot in buffer.iter_mut() {
+        if j == n || (i < mid && values[i] <= values[j]) {
+            *slot = values[i];
+            i += 1;
+        } else {
+            *slot = values[j];
+            swaps += (mid - i) as u64;
+            j += 1;
+        }
+    }
+    values.copy_from_slice(buffer);
+    swaps
+}
+
+// Kendall's tau-b in O(n log n) (Knight's algorithm): sort by (a, b), then count the swaps a
+// merge sort on b needs, correcting for pairs tied in a, in b or in both
+#[pyfunction]
+pub fn kendall_tau(py: Python<'_>, a: ArrayLike, b: ArrayLike) -> PyResult<f64> {
+    with_slice!(a, a => with_slice!(b, b => {
+        detach_large(py, a.len(), || kendall_tau_slice(a, b))
+    }))
+    .map_err(|e| e.raise("kendall_tau"))
+}
+
+fn kendall_tau_slice<A: Element, B: Element>(a: &[A], b: &[B]) -> Result<f64, Invalid> {
+    same_length("a", a.len(), "b", b.len())?;
+    if a.len() < 2 {
+        return Err(Invalid::new("a", "needs at least 2 observations"));
+    }
+    all_finite("a", a)?;
+    all_finite("b", b)?;
+    let n = a.len();
+    let (a, b) = (|i: usize| a[i].to_f64(), |i: usize| b[i].to_f64());
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_unstable_by(|&i, &j| a(i).total_cmp(&a(j)).then(b(i).total_cmp(&b(j))));
+    let tied_a = tied_pairs(order.iter().map(|&i| a(i)));
+    let tied_both = tied_pairs(order.iter().map(|&i| (a(i), b(i))));
+    let mut sorted_b: Vec<f64> = order.iter().map(|&i| b(i)).collect();
+    let swaps = count_inversions(&mut sorted_b, &mut vec![0.0; n]);
+    let tied_b = tied_pairs(sorted_b.iter());
+    let pairs = (n as u64) * (n as u64 - 1) / 2;
+    if tied_a == pairs || tied_b == pairs {
+        let argument = if tied_a == pairs { "a" } else { "b" };
+        return Err(Invalid::new(argument, "is constant, so the correlation is undefined"));
+    }
+    // Concordant minus discordant = pairs - ties - 2 * discordant, with joint ties added back once
+    let numerator = (pairs - tied_a - tied_b + tied_both) as f64 - 2.0 * swaps as f64;
+    let pairs = pairs as f64;
+    Ok((numerator / ((pairs - tied_a as f64) * (pairs - tied_b as f64)).sqrt()).clamp(-1.0, 1.0))
+}
+
+// Pearson correlation matrix of a list of equal-length series
+#[pyfunction]
+pub fn corr_matrix(py: Python<'_>, columns: Vec<ArrayLike>) -> PyResult<Matrix> {
+    if columns.is_empty() {
+        return Err(Invalid::new("columns", "cannot be empty").raise("corr_matrix"));
+    }
+    let values: Vec<Values> = columns.iter().map(ArrayLike::values).collect();
+    let work = columns.len() * columns.len() * values[0].len();
+    detach_large(py, work, || corr_matrix_values(&values)).map_err(|e| e.raise("corr_matrix"))
+}
+
+fn corr_matrix_values(columns: &[Values]) -> Result<Matrix, Invalid> {
+    let centered = centered_columns(columns, 1)?;
+    let constant = columns.iter().position(|c| match c {
+        Values::F64(values) => is_constant(values),
+        Values::F32(values) => is_constant(values),
+    });
+    if let Some(i) = constant {
+        return Err(Invalid::new("columns", format!("has a constant series at index {}, so its correlations are undefined", i)));
+    }
+    Ok(correlations(covariances(&centered, 1)))
+}
+
+// Running count, mean, variance and extremes for data that arrives in chunks
+#[pyclass(module = "fina")]
+pub struct OnlineStats {
+    acc: Welford,
+}
+
+impl OnlineStats {
+    fn when_nonempty(&self, value: f64) -> Option<f64> {
+        (self.acc.count > 0).then_some(value)
+    }
+}
+
+persist_methods!(impl OnlineStats {
+    #[new]
+    fn new() -> Self {
+        OnlineStats { acc: Welford::new() }
+    }
+
+    fn update(&mut self, x: f64) -> PyResult<()> {
+        if !x.is_finite() {
+            return Err(pyo3::exceptions::PyValueError::new_err("Value must be finite"));
+        }
+        self.acc.push(x);
+        Ok(())
+    }
+
+    // The whole batch is checked first, so a bad value leaves the accumulator untouched
+    fn update_batch(&mut self, values: ArrayLike) -> PyResult<()> {
+        with_slice!(values, values => {
+            if let Some((i, v)) = floats(values).enumerate().find(|(_, v)| !v.is_finite()) {
+                return Err(Invalid::at("values", i, v, "is not finite").raise("OnlineStats.update_batch"));
+            }
+            floats(values).for_each(|x| self.acc.push(x));
+        });
+        Ok(())
+    }
+
+    // Folds in another accumulator, as if its values had been passed to update
+    fn merge(&mut self, other: PyRef<'_, OnlineStats>) {
+        self.acc.merge(&other.acc);
+    }
+
+    fn reset(&mut self) {
+        self.acc = Welford::new();
+    }
+
+    #[getter]
+    fn count(&self) -> u64 {
+        self.acc.count
+    }
+
+    #[getter]
+    fn mean(&self) -> Option<f64> {
+        self.when_nonempty(self.acc.mean)
+    }
+
+    #[getter]
+    fn variance(&self) -> Option<f64> {
+        self.when_nonempty(self.acc.variance())
+    }
+
+    #[getter]
+    fn std(&self) -> Option<f64> {
+        self.when_nonempty(self.acc.variance().sqrt())
+    }
+
+    #[getter]
+    fn min(&self) -> Option<f64> {
+        self.when_nonempty(self.acc.min)
+    }
+
+    #[getter]
+    fn max(&self) -> Option<f64> {
+        self.when_nonempty(self.acc.max)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("OnlineStats(count={}, mean={}, std={})", self.acc.count, self.acc.mean, self.acc.variance().sqrt())
+    }
+});
+
+persist_struct!(Welford { count, mean, m2, min, max });
+persist_struct!(OnlineStats { acc }, check = |s| s.acc.m2 >= 0.0);
@@ -0,0 +1,209 @@
+use pyo3::prelude::*;
+
+use crate::errors::check_finite;
+use crate::state::{persist_methods, persist_struct};
+
+#[pyclass(module = "fina")]
+pub struct StreamingAnomalyDetector {
+    alpha: f64,
+    threshold: f64,
+    cusum_drift: f64,
+    cusum_threshold: f64,
+    warmup: usize,
+    count: usize,
+    ewma_mean: f64,
+    ewma_var: f64,
+    cusum_pos: f64,
+    cusum_neg: f64,
+}
+
+persist_methods!(impl StreamingAnomalyDetector {
+    #[new]
+    #[pyo3(signature = (alpha=0.05, threshold=3.0, cusum_drift=0.5, cusum_threshold=5.0, warmup=20))]
+    fn new(alpha: f64, threshold: f64, cusum_drift: f64, cusum_threshold: f64, warmup: usize) -> PyResult<Self> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err("Alpha must be in (0, 1]"));
+        }
+        if threshold <= 0.0 || cusum_threshold <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("Thresholds must be positive"));
+        }
+        if cusum_drift < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("cusum_drift cannot be negative"));
+        }
+        Ok(StreamingAnomalyDetector {
+            alpha,
+            threshold,
+            cusum_drift,
+            cusum_threshold,
+            warmup,
+            count: 0,
+            ewma_mean: 0.0,
+            ewma_var: 0.0,
+            cusum_pos: 0.0,
+            cusum_neg: 0.0,
+        })
+    }
+
+    fn update(&mut self, x: f64) -> PyResult<bool> {
+        if !x.is_finite() {
+            return Err(pyo3::exceptions::PyValueError::new_err("Value must be finite"));
+        }
+        self.count += 1;
+        if self.count == 1 {
+            self.ewma_mean = x;
+            self.ewma_var = 0.0;
+            return Ok(false);
+        }
+
+        let deviation = x - self.ewma_mean;
+        let std = self.ewma_var.sqrt();
+        let mut alarm = false;
+
+        if self.count > self.warmup && std > f64::EPSILON {
+            // EWMA control limits on the standardized deviation
+            let z = deviation / std;
+            if z.abs() > self.threshold {
+                alarm = true;
+            }
+
+            // Two-sided CUSUM on the same standardized deviation
+            self.cusum_pos = (self.cusum_pos + z - self.cusum_drift).max(0.0);
+            self.cusum_neg = (self.cusum_neg - z - self.cusum_drift).max(0.0);
+            if self.cusum_pos > self.cusum_threshold || self.cusum_neg > self.cusum_threshold {
+                alarm = true;
+                self.cusum_pos = 0.0;
+                self.cusum_neg = 0.0;
+            }
+        }
+
+        self.ewma_mean += self.alpha * deviation;
+        self.ewma_var = (1.0 - self.alpha) * (self.ewma_var + self.alpha * deviation * deviation);
+        Ok(alarm)
+    }
+
+    fn reset(&mut self) {
+        self.count = 0;
+        self.ewma_mean = 0.0;
+        self.ewma_var = 0.0;
+        self.cusum_pos = 0.0;
+        self.cusum_neg = 0.0;
+    }
+
+    #[getter]
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    #[getter]
+    fn mean(&self) -> f64 {
+        self.ewma_mean
+    }
+
+    #[getter]
+    fn std(&self) -> f64 {
+        self.ewma_var.sqrt()
+    }
+
+    #[getter]
+    fn cusum_pos(&self) -> f64 {
+        self.cusum_pos
+    }
+
+    #[getter]
+    fn cusum_neg(&self) -> f64 {
+        self.cusum_neg
+    }
+});
+
+// Exponentially weighted recursive least squares; forgetting < 1 discounts old observations
+#[pyclass(module = "fina")]
+pub struct RecursiveLeastSquares {
+    forgetting: f64,
+    weights: Vec<f64>,
+    p: Vec<Vec<f64>>,
+    count: usize,
+}
+
+persist_methods!(impl RecursiveLeastSquares {
+    #[new]
+    #[pyo3(signature = (n_features, forgetting=1.0, delta=1000.0))]
+    fn new(n_features: usize, forgetting: f64, delta: f64) -> PyResult<Self> {
+        if n_features == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("n_features must be positive"));
+        }
+        if !(forgetting > 0.0 && forgetting <= 1.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err("forgetting must be in (0, 1]"));
+        }
+        if delta <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("delta must be positive"));
+        }
+        // A large initial covariance means the first observations dominate the prior of zero weights
+        let p = (0..n_features)
+            .map(|i| (0..n_features).map(|j| if i == j { delta } else { 0.0 }).collect())
+            .collect();
+        Ok(RecursiveLeastSquares { forgetting, weights: vec![0.0; n_features], p, count: 0 })
+    }
+
+    fn update(&mut self, x_vec: Vec<f64>, y: f64) -> PyResult<Vec<f64>> {
+        let n = self.weights.len();
+        if x_vec.len() != n {
+            return Err(pyo3::exceptions::PyValueError::new_err("x_vec length must equal n_features"));
+        }
+        check_finite("RecursiveLeastSquares.update", "x_vec", &x_vec)?;
+        check_finite("RecursiveLeastSquares.update", "y", &[y])?;
+        let px: Vec<f64> = self.p.iter().map(|row| row.iter().zip(&x_vec).map(|(a, b)| a * b).sum()).collect();
+        let denom = self.forgetting + x_vec.iter().zip(&px).map(|(a, b)| a * b).sum::<f64>();
+        let gain: Vec<f64> = px.iter().map(|v| v / denom).collect();
+        let error = y - self.weights.iter().zip(&x_vec).map(|(w, x)| w * x).sum::<f64>();
+        for (w, k) in self.weights.iter_mut().zip(&gain) {
+            *w += k * error;
+        }
+        // P <- (P - k (P x)^T) / lambda, kept symmetric against round-off
+        for (i, &k) in gain.iter().enumerate() {
+            for (j, &pxj) in px.iter().enumerate().skip(i) {
+                let v = (self.p[i][j] - k * pxj) / self.forgetting;
+                self.p[i][j] = v;
+                self.p[j][i] = v;
+            }
+        }
+        self.count += 1;
+        Ok(self.weights.clone())
+    }
+
+    fn predict(&self, x_vec: Vec<f64>) -> PyResult<f64> {
+        if x_vec.len() != self.weights.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err("x_vec length must equal n_features"));
+        }
+        Ok(self.weights.iter().zip(&x_vec).map(|(w, x)| w * x).sum())
+    }
+
+    #[getter]
+    fn coefficients(&self) -> Vec<f64> {
+        self.weights.clone()
+    }
+
+    #[getter]
+    fn count(&self) -> usize {
+        self.count
+    }
+});
+
+persist_struct!(StreamingAnomalyDetector {
+    alpha,
+    threshold,
+    cusum_drift,
+    cusum_threshold,
+    warmup,
+    count,
+    ewma_mean,
+    ewma_var,
+    cusum_pos,
+    cusum_neg,
+});
+persist_struct!(
+    RecursiveLeastSquares { forgetting, weights, p, count },
+    check = |m| {
+        let n = m.weights.len();
+        n > 0 && m.p.len() == n && m.p.iter().all(|row| row.len() == n)
+    }
+);
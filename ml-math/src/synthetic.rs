@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+use crate::rng::Rng;
+
+type Ohlcv = (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>);
+
+const KNOWN_PARAMS: [&str; 12] = [
+    "s0",
+    "mu",
+    "sigma",
+    "omega",
+    "alpha",
+    "beta",
+    "sigma_low",
+    "sigma_high",
+    "p_stay",
+    "base_volume",
+    "volume_noise",
+    "intrabar_steps",
+];
+
+struct BarParams {
+    s0: f64,
+    mu: f64,
+    sigma: f64,
+    omega: f64,
+    alpha: f64,
+    beta: f64,
+    sigma_low: f64,
+    sigma_high: f64,
+    p_stay: f64,
+    base_volume: f64,
+    volume_noise: f64,
+    steps: usize,
+}
+
+// Per-bar log-price model: constant-vol GBM, GARCH(1,1) vol clustering, or two-state regime switching.
+// params is a dict of floats; any key left out falls back to a default, unknown keys are rejected
+#[pyfunction]
+#[pyo3(signature = (model="garch", params=None, n_bars=1000, seed=None))]
+pub fn generate_synthetic_ohlcv(
+    model: &str,
+    params: Option<HashMap<String, f64>>,
+    n_bars: usize,
+    seed: Option<u64>,
+) -> PyResult<Ohlcv> {
+    let params = params.unwrap_or_default();
+    if let Some(key) = params.keys().find(|k| !KNOWN_PARAMS.contains(&k.as_str())) {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!("Unknown parameter '{}'", key)));
+    }
+    let get = |key: &str, default: f64| params.get(key).copied().unwrap_or(default);
+    let s0 = get("s0", 100.0);
+    let mu = get("mu", 0.0);
+    let sigma = get("sigma", 0.01);
+    let (omega, alpha, beta) = (get("omega", 1e-6), get("alpha", 0.08), get("beta", 0.9));
+    let (sigma_low, sigma_high, p_stay) = (get("sigma_low", 0.008), get("sigma_high", 0.025), get("p_stay", 0.98));
+    let base_volume = get("base_volume", 1e6);
+    let volume_noise = get("volume_noise", 0.3);
+    let intrabar_steps = get("intrabar_steps", 16.0);
+
+    if s0 <= 0.0 || sigma < 0.0 || sigma_low < 0.0 || sigma_high < 0.0 || base_volume < 0.0 || volume_noise < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "s0 must be positive; vols and volume parameters cannot be negative",
+        ));
+    }
+    if !(0.0..=1.0).contains(&p_stay) {
+        return Err(pyo3::exceptions::PyValueError::new_err("p_stay must be in [0, 1]"));
+    }
+    if intrabar_steps < 1.0 || intrabar_steps.fract() != 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("intrabar_steps must be a positive integer"));
+    }
+    match model {
+        "gbm" | "regime" => {}
+        "garch" => {
+            if omega <= 0.0 || alpha < 0.0 || beta < 0.0 || alpha + beta >= 1.0 {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "GARCH needs omega > 0, non-negative alpha and beta, and alpha + beta < 1",
+                ));
+            }
+        }
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("model must be 'gbm', 'garch' or 'regime'")),
+    }
+
+    let params = BarParams {
+        s0,
+        mu,
+        sigma,
+        omega,
+        alpha,
+        beta,
+        sigma_low,
+        sigma_high,
+        p_stay,
+        base_volume,
+        volume_noise,
+        steps: intrabar_steps as usize,
+    };
+    Ok(simulate_bars(model, &params, n_bars, &mut Rng::from_seed(seed)))
+}
+
+fn simulate_bars(model: &str, p: &BarParams, n_bars: usize, rng: &mut Rng) -> Ohlcv {
+    let BarParams { s0, mu, sigma, omega, alpha, beta, sigma_low, sigma_high, p_stay, base_volume, volume_noise, steps } = *p;
+    let (mut open, mut high, mut low, mut close, mut volume) = (
+        Vec::with_capacity(n_bars),
+        Vec::with_capacity(n_bars),
+        Vec::with_capacity(n_bars),
+        Vec::with_capacity(n_bars),
+        Vec::with_capacity(n_bars),
+    );
+    let mut price = s0;
+    let mut variance = if model == "garch" { omega / (1.0 - alpha - beta) } else { sigma * sigma };
+    let mut high_regime = false;
+    for _ in 0..n_bars {
+        let bar_sigma = match model {
+            "regime" => {
+                if rng.next_f64() >= p_stay {
+                    high_regime = !high_regime;
+                }
+                if high_regime {
+                    sigma_high
+                } else {
+                    sigma_low
+                }
+            }
+            _ => variance.sqrt(),
+        };
+
+        // Walk the bar in sub-steps so the high and low come from the same path as the close
+        let bar_open = price;
+        let (mut bar_high, mut bar_low) = (price, price);
+        let sub_sigma = bar_sigma / (steps as f64).sqrt();
+        let sub_drift = (mu - 0.5 * bar_sigma * bar_sigma) / steps as f64;
+        for _ in 0..steps {
+            price *= libm::exp(sub_drift + sub_sigma * rng.normal());
+            bar_high = bar_high.max(price);
+            bar_low = bar_low.min(price);
+        }
+        let log_return = libm::log(price / bar_open);
+        if model == "garch" {
+            let shock = log_return - mu;
+            variance = omega + alpha * shock * shock + beta * variance;
+        }
+
+        // Volume rises with the size of the move relative to the bar's vol
+        let surprise = if bar_sigma > 0.0 { log_return.abs() / bar_sigma } else { 0.0 };
+        let noise = libm::exp(volume_noise * rng.normal() - 0.5 * volume_noise * volume_noise);
+        open.push(bar_open);
+        high.push(bar_high);
+        low.push(bar_low);
+        close.push(price);
+        volume.push(base_volume * (1.0 + surprise) * noise);
+    }
+    (open, high, low, close, volume)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars_are_pinned_for_a_seed() {
+        let params = BarParams {
+            s0: 100.0,
+            mu: 0.0,
+            sigma: 0.01,
+            omega: 1e-6,
+            alpha: 0.08,
+            beta: 0.9,
+            sigma_low: 0.008,
+            sigma_high: 0.025,
+            p_stay: 0.98,
+            base_volume: 1e6,
+            volume_noise: 0.3,
+            steps: 16,
+        };
+        let expected = [
+            ("gbm", 101.25232426730463, 103.40434846963136, 1754535.7644248374),
+            ("garch", 102.24621369141026, 102.66838058730679, 1755782.135629023),
+            ("regime", 87.05430369585568, 103.13797032834422, 1298123.9079911262),
+        ];
+        for (model, last_close, high, volume) in expected {
+            let (open, hi, _, close, vol) = simulate_bars(model, &params, 50, &mut Rng::new(13));
+            assert_eq!(open[0], 100.0);
+            assert_eq!((close[49], hi[10], vol[0]), (last_close, high, volume), "{}", model);
+        }
+    }
+}
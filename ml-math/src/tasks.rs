@@ -0,0 +1,106 @@
+// Runs fina functions on a background thread pool and hands back futures, so an asyncio service can
+// await a heavy computation instead of blocking its event loop. Functions release the GIL while they
+// crunch large inputs, which is what lets the event loop keep running in the meantime
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::OnceLock;
+
+use pyo3::panic::PanicException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+// Separate from rayon's global pool so a queue of submissions cannot starve the parallel kernels
+fn pool() -> &'static rayon::ThreadPool {
+    static POOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .thread_name(|i| format!("fina-task-{}", i))
+            // Task panics are caught in spawn; this only keeps a panic while reporting one from
+            // taking the process down
+            .panic_handler(|_| {})
+            .build()
+            .expect("failed to start the fina task pool")
+    })
+}
+
+fn lookup<'py>(py: Python<'py>, fn_name: &str) -> PyResult<Bound<'py, PyAny>> {
+    let module = py.import("fina")?;
+    match module.getattr(fn_name) {
+        Ok(func) if !fn_name.starts_with('_') && func.is_callable() => Ok(func),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!("fina has no function named {:?}", fn_name))),
+    }
+}
+
+fn spawn<'py>(
+    py: Python<'py>,
+    fn_name: &str,
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let func = lookup(py, fn_name)?.unbind();
+    let future = py.import("concurrent.futures")?.getattr("Future")?.call0()?;
+    let (task, args, kwargs) = (future.clone().unbind(), args.clone().unbind(), kwargs.map(|k| k.clone().unbind()));
+    pool().spawn(move || {
+        let run = || {
+            Python::attach(|py| {
+                let future = task.bind(py);
+                // False when the caller cancelled the future while it was still queued
+                if !future.call_method0("set_running_or_notify_cancel").and_then(|r| r.extract::<bool>()).unwrap_or(false) {
+                    return;
+                }
+                let outcome = func.bind(py).call(args.bind(py), kwargs.as_ref().map(|k| k.bind(py)));
+                let _ = match outcome {
+                    Ok(value) => future.call_method1("set_result", (value,)),
+                    Err(err) => future.call_method1("set_exception", (err.value(py),)),
+                };
+            })
+        };
+        // A panic outside the called function would otherwise leave the future pending forever
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(run)) {
+            let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+                (Some(message), _) => message.to_string(),
+                (_, Some(message)) => message.clone(),
+                _ => "fina task panicked".to_string(),
+            };
+            Python::attach(|py| {
+                let err = PanicException::new_err(message);
+                let _ = task.bind(py).call_method1("set_exception", (err.value(py),));
+            });
+        }
+    });
+    Ok(future)
+}
+
+// fina.submit("var_historical", returns, 0.99) -> concurrent.futures.Future. A task holds the GIL
+// except where the function itself releases it (large array inputs and the long-running functions),
+// so small or GIL-bound calls run one at a time and still pause other Python threads
+#[pyfunction]
+#[pyo3(signature = (fn_name, *args, **kwargs))]
+pub fn submit<'py>(
+    py: Python<'py>,
+    fn_name: &str,
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    spawn(py, fn_name, args, kwargs)
+}
+
+// await fina.submit_async("var_historical", returns, 0.99) from inside a running event loop; the
+// same GIL caveat as submit applies, so the loop only runs freely while the function has released it
+#[pyfunction]
+#[pyo3(signature = (fn_name, *args, **kwargs))]
+pub fn submit_async<'py>(
+    py: Python<'py>,
+    fn_name: &str,
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let asyncio = py.import("asyncio")?;
+    let event_loop = asyncio
+        .call_method0("get_running_loop")
+        .map_err(|_| pyo3::exceptions::PyRuntimeError::new_err("submit_async must be called from a running event loop"))?;
+    let future = spawn(py, fn_name, args, kwargs)?;
+    let wrap_kwargs = PyDict::new(py);
+    wrap_kwargs.set_item("loop", event_loop)?;
+    asyncio.call_method("wrap_future", (future,), Some(&wrap_kwargs))
+}
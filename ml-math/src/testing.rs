@@ -0,0 +1,169 @@
+// fina.testing: tolerance checks and generated test vectors for comparing fina against a
+// reference implementation (NumPy, SciPy, pure Python) while migrating
+
+use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+
+use crate::rng::Rng;
+
+// A float or a sequence of floats
+#[derive(FromPyObject)]
+enum Floats {
+    One(f64),
+    Many(Vec<f64>),
+}
+
+impl Floats {
+    fn into_vec(self) -> (Vec<f64>, bool) {
+        match self {
+            Floats::One(x) => (vec![x], true),
+            Floats::Many(v) => (v, false),
+        }
+    }
+}
+
+// Maps a float's bits onto integers that are ordered like the floats, so adjacent floats
+// differ by one; -0.0 and 0.0 coincide
+fn ordered_bits(x: f64) -> i128 {
+    let bits = x.to_bits() as i64;
+    (if bits < 0 { i64::MIN - bits } else { bits }) as i128
+}
+
+fn ulps(a: f64, b: f64) -> PyResult<u64> {
+    if a.is_nan() || b.is_nan() {
+        return Err(pyo3::exceptions::PyValueError::new_err("ulp_diff is undefined for NaN"));
+    }
+    Ok((ordered_bits(a) - ordered_bits(b)).unsigned_abs().min(u64::MAX as u128) as u64)
+}
+
+// Number of representable doubles between a and b, elementwise for sequences
+#[pyfunction]
+fn ulp_diff(py: Python<'_>, a: Floats, b: Floats) -> PyResult<Py<PyAny>> {
+    let (a, scalar) = a.into_vec();
+    let (b, _) = b.into_vec();
+    if a.len() != b.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!("Lengths differ: {} and {}", a.len(), b.len())));
+    }
+    let diffs = a.iter().zip(&b).map(|(&x, &y)| ulps(x, y)).collect::<PyResult<Vec<u64>>>()?;
+    if scalar {
+        diffs[0].into_py_any(py)
+    } else {
+        diffs.into_py_any(py)
+    }
+}
+
+// Description of the first mismatch under |actual - desired| <= atol + rtol * |desired|,
+// the rule numpy.testing.assert_allclose uses
+fn mismatch(actual: &[f64], desired: &[f64], rtol: f64, atol: f64, equal_nan: bool) -> Option<String> {
+    if actual.len() != desired.len() {
+        return Some(format!("Lengths differ: {} and {}", actual.len(), desired.len()));
+    }
+    let close = |a: f64, d: f64| a == d || (equal_nan && a.is_nan() && d.is_nan()) || (a - d).abs() <= atol + rtol * d.abs();
+    let bad: Vec<usize> = (0..actual.len()).filter(|&i| !close(actual[i], desired[i])).collect();
+    let first = *bad.first()?;
+    let abs_diff = bad.iter().map(|&i| (actual[i] - desired[i]).abs()).fold(0.0, f64::max);
+    let rel_diff = bad.iter().map(|&i| ((actual[i] - desired[i]) / desired[i]).abs()).fold(0.0, f64::max);
+    Some(format!(
+        "Not close (rtol={:?}, atol={:?}): {} of {} elements differ; first at index {}: {} vs {}; max abs diff {}, max rel diff {}",
+        rtol,
+        atol,
+        bad.len(),
+        actual.len(),
+        first,
+        actual[first],
+        desired[first],
+        abs_diff,
+        rel_diff
+    ))
+}
+
+// Raises AssertionError describing the mismatches unless actual and desired agree
+#[pyfunction]
+#[pyo3(signature = (actual, desired, rtol=1e-7, atol=0.0, equal_nan=true))]
+fn assert_close(actual: Floats, desired: Floats, rtol: f64, atol: f64, equal_nan: bool) -> PyResult<()> {
+    let (actual, _) = actual.into_vec();
+    let (desired, _) = desired.into_vec();
+    match mismatch(&actual, &desired, rtol, atol, equal_nan) {
+        Some(message) => Err(pyo3::exceptions::PyAssertionError::new_err(message)),
+        None => Ok(()),
+    }
+}
+
+fn generate(kind: &str, size: usize, rng: &mut Rng) -> PyResult<Vec<f64>> {
+    Ok(match kind {
+        "normal" => (0..size).map(|_| rng.normal()).collect(),
+        "uniform" => (0..size).map(|_| rng.next_f64()).collect(),
+        "lognormal" => (0..size).map(|_| libm::exp(rng.normal())).collect(),
+        // A large offset with small noise: catastrophic cancellation for naive variance
+        "offset" => (0..size).map(|_| 1e9 + rng.normal() * 1e-3).collect(),
+        // Magnitudes from 1e-300 to 1e300 with both signs, zeros and subnormals mixed in
+        "extreme" => (0..size)
+            .map(|_| match rng.below(8) {
+                0 => 0.0,
+                1 => f64::MIN_POSITIVE / 4.0,
+                _ => {
+                    let sign = if rng.below(2) == 0 { -1.0 } else { 1.0 };
+                    sign * libm::pow(10.0, rng.next_f64() * 600.0 - 300.0)
+                }
+            })
+            .collect(),
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "kind must be 'normal', 'uniform', 'lognormal', 'offset' or 'extreme'",
+            ))
+        }
+    })
+}
+
+// A generated test vector; the same seed always gives the same vector
+#[pyfunction]
+#[pyo3(signature = (kind, size, seed=None))]
+fn test_vector(kind: &str, size: usize, seed: Option<u64>) -> PyResult<Vec<f64>> {
+    generate(kind, size, &mut Rng::from_seed(seed))
+}
+
+// Calls func and reference on n_cases generated vectors (case i uses seed + i) and asserts
+// their outputs are close; the AssertionError names the failing case's seed so it can be
+// reproduced with test_vector. Returns the number of cases checked
+#[pyfunction]
+#[pyo3(signature = (func, reference, n_cases=100, size=100, kind="normal", rtol=1e-7, atol=0.0, seed=0))]
+#[allow(clippy::too_many_arguments)]
+fn compare(
+    py: Python<'_>,
+    func: &Bound<'_, PyAny>,
+    reference: &Bound<'_, PyAny>,
+    n_cases: usize,
+    size: usize,
+    kind: &str,
+    rtol: f64,
+    atol: f64,
+    seed: u64,
+) -> PyResult<usize> {
+    for case in 0..n_cases {
+        let case_seed = seed.wrapping_add(case as u64);
+        let data = generate(kind, size, &mut Rng::new(case_seed))?;
+        let (actual, _) = func.call1((data.clone(),))?.extract::<Floats>()?.into_vec();
+        let (desired, _) = reference.call1((data,))?.extract::<Floats>()?.into_vec();
+        if let Some(message) = mismatch(&actual, &desired, rtol, atol, true) {
+            return Err(pyo3::exceptions::PyAssertionError::new_err(format!(
+                "Case {} (test_vector('{}', {}, seed={})): {}",
+                case, kind, size, case_seed, message
+            )));
+        }
+        py.check_signals()?;
+    }
+    Ok(n_cases)
+}
+
+// Builds fina.testing and registers it in sys.modules so `import fina.testing` works
+pub fn register(parent: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = parent.py();
+    let m = PyModule::new(py, "fina.testing")?;
+    m.add_function(wrap_pyfunction!(assert_close, &m)?)?;
+    m.add_function(wrap_pyfunction!(ulp_diff, &m)?)?;
+    m.add_function(wrap_pyfunction!(test_vector, &m)?)?;
+    m.add_function(wrap_pyfunction!(compare, &m)?)?;
+    parent.add("testing", &m)?;
+    py.import("sys")?.getattr("modules")?.set_item("fina.testing", &m)?;
+    Ok(())
+}
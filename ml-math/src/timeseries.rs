@@ -0,0 +1,454 @@
+use pyo3::prelude::*;
+
+use crate::errors::{check_finite, Invalid};
+use crate::linalg::solve_dense;
+
+fn within_tolerance(data: &[f64], i: usize, j: usize, m: usize, r: f64) -> bool {
+    (0..m).all(|k| (data[i + k] - data[j + k]).abs() <= r)
+}
+
+fn validate_entropy_args(data: &[f64], m: usize, r: f64) -> PyResult<()> {
+    if m == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("m must be at least 1"));
+    }
+    if data.len() <= m + 1 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data must be longer than m + 1"));
+    }
+    if r < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("r cannot be negative"));
+    }
+    Ok(())
+}
+
+#[pyfunction]
+pub fn sample_entropy(data: Vec<f64>, m: usize, r: f64) -> PyResult<f64> {
+    validate_entropy_args(&data, m, r)?;
+
+    // Both template lengths use the same n - m starting points, self-matches excluded
+    let templates = data.len() - m;
+    let mut b = 0u64;
+    let mut a = 0u64;
+    for i in 0..templates {
+        for j in (i + 1)..templates {
+            if within_tolerance(&data, i, j, m, r) {
+                b += 1;
+                if (data[i + m] - data[j + m]).abs() <= r {
+                    a += 1;
+                }
+            }
+        }
+    }
+
+    if a == 0 || b == 0 {
+        // No matches of length m + 1: entropy is unbounded
+        return Ok(f64::INFINITY);
+    }
+    Ok(-(a as f64 / b as f64).ln())
+}
+
+#[pyfunction]
+pub fn approximate_entropy(data: Vec<f64>, m: usize, r: f64) -> PyResult<f64> {
+    validate_entropy_args(&data, m, r)?;
+
+    let phi = |len: usize| -> f64 {
+        let count = data.len() - len + 1;
+        let mut total = 0.0;
+        for i in 0..count {
+            // Self-match guarantees at least one hit
+            let matches = (0..count).filter(|&j| within_tolerance(&data, i, j, len, r)).count();
+            total += (matches as f64 / count as f64).ln();
+        }
+        total / count as f64
+    };
+    Ok(phi(m) - phi(m + 1))
+}
+
+#[pyfunction]
+pub fn shannon_entropy(probs: Vec<f64>) -> PyResult<f64> {
+    if probs.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Probabilities cannot be empty"));
+    }
+    if let Some(i) = probs.iter().position(|&p| !(p.is_finite() && p >= 0.0)) {
+        return Err(Invalid::at("probs", i, probs[i], "is not a non-negative probability").raise("shannon_entropy"));
+    }
+    let total: f64 = probs.iter().sum();
+    if total <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Probabilities must sum to a positive value"));
+    }
+    Ok(-probs.iter().filter(|&&p| p > 0.0).map(|&p| (p / total) * (p / total).ln()).sum::<f64>())
+}
+
+fn true_runs<I: IntoIterator<Item = bool>>(flags: I) -> (Vec<usize>, Vec<usize>) {
+    let mut starts = Vec::new();
+    let mut lengths = Vec::new();
+    let mut current: Option<usize> = None;
+    let mut len = 0;
+    for (i, flag) in flags.into_iter().enumerate() {
+        len = i + 1;
+        match (flag, current) {
+            (true, None) => current = Some(i),
+            (false, Some(start)) => {
+                starts.push(start);
+                lengths.push(i - start);
+                current = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = current {
+        starts.push(start);
+        lengths.push(len - start);
+    }
+    (starts, lengths)
+}
+
+#[pyfunction]
+pub fn run_lengths(bool_array: Vec<bool>) -> PyResult<(Vec<usize>, Vec<usize>)> {
+    Ok(true_runs(bool_array))
+}
+
+#[pyfunction]
+#[pyo3(signature = (returns, positive=true))]
+pub fn longest_streak(returns: Vec<f64>, positive: bool) -> PyResult<usize> {
+    // Zero returns break a streak in either direction
+    let (_, lengths) = true_runs(returns.iter().map(|&r| if positive { r > 0.0 } else { r < 0.0 }));
+    Ok(lengths.into_iter().max().unwrap_or(0))
+}
+
+// Least-squares line over data[start..=end]; returns (slope, intercept at start, max abs residual)
+fn fit_line(data: &[f64], start: usize, end: usize) -> (f64, f64, f64) {
+    let n = (end - start + 1) as f64;
+    if end == start {
+        return (0.0, data[start], 0.0);
+    }
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = data[start..=end].iter().sum::<f64>() / n;
+    let mut sxy = 0.0;
+    let mut sxx = 0.0;
+    for (i, &y) in data[start..=end].iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        sxy += dx * (y - mean_y);
+        sxx += dx * dx;
+    }
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let max_err = data[start..=end]
+        .iter()
+        .enumerate()
+        .map(|(i, &y)| (y - (intercept + slope * i as f64)).abs())
+        .fold(0.0, f64::max);
+    (slope, intercept, max_err)
+}
+
+#[pyfunction]
+pub fn segment_series(data: Vec<f64>, max_error: f64) -> PyResult<(Vec<usize>, Vec<f64>, Vec<f64>)> {
+    if data.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+    }
+    if max_error < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("max_error cannot be negative"));
+    }
+
+    // Bottom-up PLA: start from two-point segments and repeatedly merge the cheapest
+    // adjacent pair. Segments form a linked list, the heap holds stale-checked merge costs.
+    let n = data.len();
+    let starts: Vec<usize> = (0..n).step_by(2).collect();
+    let mut ends: Vec<usize> = starts.iter().map(|&s| (s + 1).min(n - 1)).collect();
+    let m = starts.len();
+    let mut next: Vec<Option<usize>> = (0..m).map(|i| if i + 1 < m { Some(i + 1) } else { None }).collect();
+    let mut prev: Vec<Option<usize>> = (0..m).map(|i| if i > 0 { Some(i - 1) } else { None }).collect();
+    let mut alive = vec![true; m];
+    let mut version = vec![0u64; m];
+
+    #[derive(PartialEq)]
+    struct Merge(f64, usize, u64, usize, u64);
+    impl Eq for Merge {}
+    impl PartialOrd for Merge {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Merge {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+        }
+    }
+
+    let mut heap = std::collections::BinaryHeap::new();
+    for i in 0..m.saturating_sub(1) {
+        let cost = fit_line(&data, starts[i], ends[i + 1]).2;
+        heap.push(Merge(cost, i, 0, i + 1, 0));
+    }
+
+    while let Some(Merge(cost, left, left_version, right, right_version)) = heap.pop() {
+        if cost > max_error {
+            break;
+        }
+        if !alive[left] || !alive[right] || version[left] != left_version || version[right] != right_version {
+            continue;
+        }
+        ends[left] = ends[right];
+        alive[right] = false;
+        next[left] = next[right];
+        if let Some(r) = next[right] {
+            prev[r] = Some(left);
+        }
+        version[left] += 1;
+        if let Some(p) = prev[left] {
+            heap.push(Merge(fit_line(&data, starts[p], ends[left]).2, p, version[p], left, version[left]));
+        }
+        if let Some(r) = next[left] {
+            heap.push(Merge(fit_line(&data, starts[left], ends[r]).2, left, version[left], r, version[r]));
+        }
+    }
+
+    let mut breakpoints = Vec::new();
+    let mut slopes = Vec::new();
+    let mut intercepts = Vec::new();
+    let mut seg = Some(0);
+    while let Some(i) = seg {
+        let (slope, intercept, _) = fit_line(&data, starts[i], ends[i]);
+        breakpoints.push(starts[i]);
+        slopes.push(slope);
+        intercepts.push(intercept);
+        seg = next[i];
+    }
+    Ok((breakpoints, slopes, intercepts))
+}
+
+#[pyfunction]
+pub fn matrix_profile(series: Vec<f64>, window: usize) -> PyResult<(Vec<f64>, Vec<usize>)> {
+    if window < 3 {
+        return Err(pyo3::exceptions::PyValueError::new_err("window must be at least 3"));
+    }
+    if series.len() < 2 * window {
+        return Err(pyo3::exceptions::PyValueError::new_err("Series must be at least twice the window length"));
+    }
+    check_finite("matrix_profile", "series", &series)?;
+
+    let n = series.len();
+    let count = n - window + 1;
+    let w = window as f64;
+    let exclusion = window.div_ceil(4);
+
+    let mut sum = vec![0.0; n + 1];
+    let mut sum_sq = vec![0.0; n + 1];
+    for (i, &x) in series.iter().enumerate() {
+        sum[i + 1] = sum[i] + x;
+        sum_sq[i + 1] = sum_sq[i] + x * x;
+    }
+    let means: Vec<f64> = (0..count).map(|i| (sum[i + window] - sum[i]) / w).collect();
+    let stds: Vec<f64> = (0..count)
+        .map(|i| ((sum_sq[i + window] - sum_sq[i]) / w - means[i] * means[i]).max(0.0).sqrt())
+        .collect();
+
+    // z-normalized Euclidean distance from the sliding dot product
+    let distance = |i: usize, j: usize, qt: f64| -> f64 {
+        let flat_i = stds[i] < 1e-12;
+        let flat_j = stds[j] < 1e-12;
+        match (flat_i, flat_j) {
+            (true, true) => 0.0,
+            (true, false) | (false, true) => w.sqrt(),
+            _ => {
+                let corr = (qt - w * means[i] * means[j]) / (w * stds[i] * stds[j]);
+                (2.0 * w * (1.0 - corr)).max(0.0).sqrt()
+            }
+        }
+    };
+
+    let first_row: Vec<f64> = (0..count)
+        .map(|j| series[..window].iter().zip(&series[j..j + window]).map(|(a, b)| a * b).sum())
+        .collect();
+
+    let mut profile = vec![f64::INFINITY; count];
+    let mut index = vec![0usize; count];
+    let mut qt = first_row.clone();
+
+    // STOMP: each row of dot products is updated from the previous one in O(1) per entry
+    for i in 0..count {
+        if i > 0 {
+            for j in (1..count).rev() {
+                qt[j] = qt[j - 1] - series[i - 1] * series[j - 1] + series[i + window - 1] * series[j + window - 1];
+            }
+            qt[0] = first_row[i];
+        }
+        for (j, &dot) in qt.iter().enumerate() {
+            if i.abs_diff(j) < exclusion {
+                continue;
+            }
+            let d = distance(i, j, dot);
+            if d < profile[i] {
+                profile[i] = d;
+                index[i] = j;
+            }
+        }
+    }
+    Ok((profile, index))
+}
+
+// Least-squares polynomial trend, fitted on x rescaled to [-1, 1] for conditioning
+fn polynomial_trend(data: &[f64], degree: usize) -> PyResult<Vec<f64>> {
+    let n = data.len();
+    let xs: Vec<f64> = (0..n).map(|i| if n > 1 { 2.0 * i as f64 / (n - 1) as f64 - 1.0 } else { 0.0 }).collect();
+    let terms = degree + 1;
+    let mut ata = vec![vec![0.0; terms]; terms];
+    let mut aty = vec![0.0; terms];
+    for (&x, &y) in xs.iter().zip(data.iter()) {
+        let powers: Vec<f64> = (0..terms).map(|p| x.powi(p as i32)).collect();
+        for r in 0..terms {
+            aty[r] += powers[r] * y;
+            for c in 0..terms {
+                ata[r][c] += powers[r] * powers[c];
+            }
+        }
+    }
+    let coeffs = solve_dense(ata, aty)
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Polynomial fit is singular"))?;
+    Ok(xs.iter().map(|&x| coeffs.iter().rev().fold(0.0, |acc, &c| acc * x + c)).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, method="linear", degree=2))]
+pub fn detrend(data: Vec<f64>, method: &str, degree: usize) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    if data.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+    }
+    let trend = match method {
+        "linear" => polynomial_trend(&data, 1.min(data.len() - 1))?,
+        "poly" => {
+            if degree >= data.len() {
+                return Err(pyo3::exceptions::PyValueError::new_err("degree must be smaller than the data length"));
+            }
+            polynomial_trend(&data, degree)?
+        }
+        // Previous value as the trend, so residual + trend reproduces the input
+        "diff" => std::iter::once(data[0]).chain(data[..data.len() - 1].iter().copied()).collect(),
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("method must be 'linear', 'poly' or 'diff'")),
+    };
+    let residual = data.iter().zip(trend.iter()).map(|(x, t)| x - t).collect();
+    Ok((residual, trend))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, dx=1.0))]
+pub fn gradient(data: Vec<f64>, dx: f64) -> PyResult<Vec<f64>> {
+    if data.len() < 2 {
+        return Err(pyo3::exceptions::PyValueError::new_err("Need at least two points"));
+    }
+    if dx == 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("dx cannot be zero"));
+    }
+    // Central differences inside, one-sided differences at the edges (numpy.gradient)
+    let n = data.len();
+    let mut out = Vec::with_capacity(n);
+    out.push((data[1] - data[0]) / dx);
+    for i in 1..n - 1 {
+        out.push((data[i + 1] - data[i - 1]) / (2.0 * dx));
+    }
+    out.push((data[n - 1] - data[n - 2]) / dx);
+    Ok(out)
+}
+
+fn trapezoids(y: &[f64], x: Option<&[f64]>, dx: f64) -> PyResult<Vec<f64>> {
+    if let Some(x) = x {
+        if x.len() != y.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err("x and y must be same length"));
+        }
+    }
+    Ok((1..y.len())
+        .map(|i| {
+            let width = x.map_or(dx, |x| x[i] - x[i - 1]);
+            0.5 * width * (y[i] + y[i - 1])
+        })
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (y, x=None, dx=1.0))]
+pub fn trapz(y: Vec<f64>, x: Option<Vec<f64>>, dx: f64) -> PyResult<f64> {
+    Ok(trapezoids(&y, x.as_deref(), dx)?.iter().sum())
+}
+
+#[pyfunction]
+#[pyo3(signature = (y, x=None, dx=1.0))]
+pub fn cumtrapz(y: Vec<f64>, x: Option<Vec<f64>>, dx: f64) -> PyResult<Vec<f64>> {
+    // Starts at 0.0 so the output lines up with y
+    let mut total = 0.0;
+    let mut out = Vec::with_capacity(y.len());
+    if !y.is_empty() {
+        out.push(0.0);
+    }
+    for area in trapezoids(&y, x.as_deref(), dx)? {
+        total += area;
+        out.push(total);
+    }
+    Ok(out)
+}
+
+// Exponentially weighted variance with pandas' ewm(alpha=..., adjust=...).var(bias=...) semantics.
+// adjust=True weights observation i back by (1 - alpha)^i over the normalized sum of weights,
+// adjust=False uses the recursive form; bias=False rescales by the effective sample size, which
+// leaves the first value NaN
+fn ewm_variance(data: &[f64], alpha: f64, adjust: bool, bias: bool) -> Vec<f64> {
+    let decay = 1.0 - alpha;
+    let new_wt = if adjust { 1.0 } else { alpha };
+    let mut mean = data[0];
+    let mut var = 0.0;
+    let (mut sum_wt, mut sum_wt2, mut old_wt) = (1.0, 1.0, 1.0);
+    let finish = |var: f64, sum_wt: f64, sum_wt2: f64| {
+        if bias {
+            return var;
+        }
+        let numerator = sum_wt * sum_wt;
+        let denominator = numerator - sum_wt2;
+        if denominator > 0.0 {
+            numerator / denominator * var
+        } else {
+            f64::NAN
+        }
+    };
+    let mut out = Vec::with_capacity(data.len());
+    out.push(finish(var, sum_wt, sum_wt2));
+    for &x in &data[1..] {
+        sum_wt *= decay;
+        sum_wt2 *= decay * decay;
+        old_wt *= decay;
+        let old_mean = mean;
+        mean = (old_wt * old_mean + new_wt * x) / (old_wt + new_wt);
+        var = (old_wt * (var + (old_mean - mean).powi(2)) + new_wt * (x - mean).powi(2)) / (old_wt + new_wt);
+        sum_wt += new_wt;
+        sum_wt2 += new_wt * new_wt;
+        old_wt += new_wt;
+        if !adjust {
+            sum_wt /= old_wt;
+            sum_wt2 /= old_wt * old_wt;
+            old_wt = 1.0;
+        }
+        out.push(finish(var, sum_wt, sum_wt2));
+    }
+    out
+}
+
+fn check_ewm(function: &str, data: &[f64], alpha: f64) -> PyResult<()> {
+    if data.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Data cannot be empty"));
+    }
+    if !(alpha > 0.0 && alpha <= 1.0) {
+        return Err(pyo3::exceptions::PyValueError::new_err("Alpha must be in (0, 1]"));
+    }
+    check_finite(function, "data", data)
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, alpha, adjust=true, bias=false))]
+pub fn ewm_var(data: Vec<f64>, alpha: f64, adjust: bool, bias: bool) -> PyResult<Vec<f64>> {
+    check_ewm("ewm_var", &data, alpha)?;
+    Ok(ewm_variance(&data, alpha, adjust, bias))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, alpha, adjust=true, bias=false))]
+pub fn ewm_std(data: Vec<f64>, alpha: f64, adjust: bool, bias: bool) -> PyResult<Vec<f64>> {
+    check_ewm("ewm_std", &data, alpha)?;
+    Ok(ewm_variance(&data, alpha, adjust, bias).into_iter().map(f64::sqrt).collect())
+}
@@ -0,0 +1,150 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PySlice};
+use pyo3::IntoPyObjectExt;
+
+use crate::input::{floats, ArrayLike};
+use crate::state::{persist_methods, persist_struct};
+use crate::with_slice;
+
+// Right-hand side of an arithmetic operator: another Vector or a scalar
+#[derive(FromPyObject)]
+enum Operand<'py> {
+    Vector(PyRef<'py, Vector>),
+    Scalar(f64),
+}
+
+#[pyclass(module = "fina", sequence)]
+pub struct Vector {
+    pub(crate) data: Vec<f64>,
+}
+
+impl Vector {
+    fn zip_with(&self, other: Operand, op: impl Fn(f64, f64) -> f64) -> PyResult<Vector> {
+        let data = match other {
+            Operand::Vector(v) => {
+                if v.data.len() != self.data.len() {
+                    return Err(pyo3::exceptions::PyValueError::new_err("Vectors must be same length"));
+                }
+                self.data.iter().zip(&v.data).map(|(&a, &b)| op(a, b)).collect()
+            }
+            Operand::Scalar(s) => self.data.iter().map(|&a| op(a, s)).collect(),
+        };
+        Ok(Vector { data })
+    }
+
+    fn index(&self, index: isize) -> PyResult<usize> {
+        let n = self.data.len() as isize;
+        let i = if index < 0 { index + n } else { index };
+        if i < 0 || i >= n {
+            return Err(pyo3::exceptions::PyIndexError::new_err("Vector index out of range"));
+        }
+        Ok(i as usize)
+    }
+
+    fn norm_value(&self) -> f64 {
+        self.data.iter().map(|x| x * x).sum::<f64>().sqrt()
+    }
+}
+
+persist_methods!(impl Vector {
+    #[new]
+    fn new(data: ArrayLike) -> Self {
+        Vector { data: with_slice!(data, data => floats(data).collect()) }
+    }
+
+    fn __len__(&self) -> usize {
+        self.data.len()
+    }
+
+    fn __getitem__(&self, py: Python<'_>, index: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        if let Ok(slice) = index.cast::<PySlice>() {
+            let idx = slice.indices(self.data.len() as isize)?;
+            let data: Vec<f64> = (0..idx.slicelength)
+                .map(|k| self.data[(idx.start + k as isize * idx.step) as usize])
+                .collect();
+            return Vector { data }.into_py_any(py);
+        }
+        let i = self.index(index.extract()?)?;
+        self.data[i].into_py_any(py)
+    }
+
+    fn __setitem__(&mut self, index: isize, value: f64) -> PyResult<()> {
+        let i = self.index(index)?;
+        self.data[i] = value;
+        Ok(())
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(PyList::new(py, &self.data)?.call_method0("__iter__")?.unbind())
+    }
+
+    fn __add__(&self, other: Operand) -> PyResult<Vector> {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    fn __radd__(&self, other: Operand) -> PyResult<Vector> {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    fn __sub__(&self, other: Operand) -> PyResult<Vector> {
+        self.zip_with(other, |a, b| a - b)
+    }
+
+    fn __rsub__(&self, other: Operand) -> PyResult<Vector> {
+        self.zip_with(other, |a, b| b - a)
+    }
+
+    // Elementwise product with a Vector, scaling with a number
+    fn __mul__(&self, other: Operand) -> PyResult<Vector> {
+        self.zip_with(other, |a, b| a * b)
+    }
+
+    fn __rmul__(&self, other: Operand) -> PyResult<Vector> {
+        self.zip_with(other, |a, b| a * b)
+    }
+
+    fn __truediv__(&self, other: Operand) -> PyResult<Vector> {
+        self.zip_with(other, |a, b| a / b)
+    }
+
+    fn __neg__(&self) -> Vector {
+        Vector { data: self.data.iter().map(|x| -x).collect() }
+    }
+
+    fn __eq__(&self, other: PyRef<Vector>) -> bool {
+        self.data == other.data
+    }
+
+    fn dot(&self, other: PyRef<Vector>) -> PyResult<f64> {
+        if other.data.len() != self.data.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err("Vectors must be same length"));
+        }
+        Ok(self.data.iter().zip(&other.data).map(|(a, b)| a * b).sum())
+    }
+
+    fn norm(&self) -> f64 {
+        self.norm_value()
+    }
+
+    fn normalize(&self) -> PyResult<Vector> {
+        let norm = self.norm_value();
+        if norm < f64::EPSILON {
+            return Err(pyo3::exceptions::PyValueError::new_err("Cannot normalize a zero vector"));
+        }
+        Ok(Vector { data: self.data.iter().map(|x| x / norm).collect() })
+    }
+
+    fn sum(&self) -> f64 {
+        self.data.iter().sum()
+    }
+
+    fn tolist(&self) -> Vec<f64> {
+        self.data.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Vector({:?})", self.data)
+    }
+});
+
+persist_struct!(Vector { data });